@@ -1,6 +1,13 @@
-use clap::Parser;
+use std::thread;
 
-use problem::*;
+use clap::{Parser, Subcommand};
+
+use eia::problem::export::FormatoExport;
+use eia::problem::formats::Formato;
+use eia::problem::graph::{Duplicati, GraphType, LoadOptions};
+use eia::problem::heuristic::Euristica;
+use eia::problem::*;
+use eia::problems::Dominio;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -9,17 +16,23 @@ use problem::*;
     long_about = "Progetto per l'esame di Elementi di Intelligenza Artificiale, realizzato da Adriano Oliviero (N46006115)."
 )]
 pub struct Args {
-    /// File contenente il dataset
+    /// File contenente il dataset (testo, gzip, bzip2 o zstd), "-" per lo standard input
     #[arg(short = 'F', long = "file", default_value = "data/email-Enron.txt.gz")]
     pub file: Option<String>,
 
-    /// Stato iniziale
+    #[command(subcommand)]
+    pub comando: Option<Comando>,
+
+    #[command(flatten)]
+    pub grafo: OpzioniGrafo,
+
+    /// Nodo iniziale, come compare nel dataset
     #[arg(short = 'i', long = "inizia", default_value = "46")]
-    pub stato_iniziale: Option<u32>,
+    pub stato_iniziale: Option<String>,
 
-    /// Stato finale
+    /// Nodo finale, come compare nel dataset
     #[arg(short = 'f', long = "finale", default_value = "73")]
-    pub stato_finale: Option<u32>,
+    pub stato_finale: Option<String>,
 
     // Esegue tutti gli algoritmi di ricerca
     #[arg(
@@ -31,8 +44,12 @@ pub struct Args {
     )]
     pub all: bool,
 
-    #[arg(short = 'l', long="limite", default_value = "10")]
-    pub limite: usize,
+    #[command(flatten)]
+    pub opzioni: OpzioniRicerca,
+
+    /// Stampa il cammino trovato da ogni algoritmo
+    #[arg(short = 'p', long = "path", action, default_value = "false")]
+    pub path: bool,
 
     /// Esegue un algoritmo di ricerca specifico
     #[arg(
@@ -42,17 +59,270 @@ pub struct Args {
         default_value = "bi-directional"
     )]
     pub ricerca: Option<Ricerca>,
+
+    /// Problema da risolvere
+    #[arg(long = "problem", default_value = "graph")]
+    pub dominio: Dominio,
+
+    /// Tessere del puzzle riga per riga, 0 è la casella vuota
+    #[arg(
+        long = "tiles",
+        value_delimiter = ',',
+        default_value = "3,2,5,6,0,8,7,1,4"
+    )]
+    pub tessere: Vec<u8>,
+
+    /// Numero di regine
+    #[arg(long = "queens", default_value = "8")]
+    pub regine: usize,
+
+    /// File contenente il labirinto
+    #[arg(long = "maze", required_if_eq("dominio", "maze"))]
+    pub labirinto: Option<String>,
+
+    /// Città di partenza sulla mappa della Romania
+    #[arg(long = "from", default_value = "Arad")]
+    pub partenza: String,
+
+    /// Città di arrivo sulla mappa della Romania
+    #[arg(long = "to", default_value = "Bucharest")]
+    pub arrivo: String,
 }
 
 impl Default for Args {
     fn default() -> Self {
         Args {
             file: Some("email-Enron.txt.gz".into()),
-            stato_iniziale: Some(46),
-            stato_finale: Some(73),
+            comando: None,
+            grafo: OpzioniGrafo::default(),
+            stato_iniziale: Some("46".into()),
+            stato_finale: Some("73".into()),
             all: true,
-            limite: 10,
+            opzioni: OpzioniRicerca::default(),
+            path: false,
             ricerca: Some(Ricerca::BiDirectional),
+            dominio: Dominio::Graph,
+            tessere: vec![3, 2, 5, 6, 0, 8, 7, 1, 4],
+            regine: 8,
+            labirinto: None,
+            partenza: "Arad".into(),
+            arrivo: "Bucharest".into(),
+        }
+    }
+}
+
+/// Opzioni delle ricerche, comuni alla ricerca e al sottocomando export
+#[derive(clap::Args, Debug)]
+pub struct OpzioniRicerca {
+    /// Profondità a cui la ricerca depth-limited interrompe l'esplorazione
+    #[arg(short = 'l', long = "limite", default_value = "10")]
+    pub limite: usize,
+
+    /// Profondità massima provata dalla ricerca iterative-deepening
+    #[arg(long = "max-depth", default_value = "50")]
+    pub profondita_massima: usize,
+
+    /// Le ricerche a profondità limitata scartano gli stati già presenti sul cammino corrente
+    #[arg(long = "cycle-check", action, default_value = "false")]
+    pub controllo_cicli: bool,
+
+    /// Esegue gli algoritmi come ricerche su albero o su grafo (se omessa, quella propria di ogni algoritmo)
+    #[arg(long = "mode")]
+    pub modalita: Option<Modalita>,
+
+    /// Nodi che una ricerca su albero può espandere prima di essere interrotta
+    #[arg(long = "max-expansions", default_value = "1000000")]
+    pub espansioni_massime: usize,
+
+    /// Euristica usata dalle ricerche informate
+    #[arg(long = "heuristic", default_value = "zero")]
+    pub euristica: Euristica,
+
+    /// Nodi usati come landmark dall'euristica landmark (4 nodi casuali se omessi)
+    #[arg(long = "landmarks", value_delimiter = ',')]
+    pub landmarks: Vec<String>,
+
+    /// File con una coppia "stato valore" per riga, usato dall'euristica file
+    #[arg(long = "heuristic-file", required_if_eq("euristica", "file"))]
+    pub file_euristica: Option<String>,
+
+    /// File .co DIMACS con le coordinate dei nodi, usato dall'euristica euclidean
+    #[arg(long = "coordinates", required_if_eq("euristica", "euclidean"))]
+    pub coordinate: Option<String>,
+
+    /// Fattore per cui è moltiplicata la distanza tra le coordinate
+    #[arg(long = "coordinate-scale", default_value = "1.0")]
+    pub scala_coordinate: f64,
+}
+
+impl Default for OpzioniRicerca {
+    fn default() -> Self {
+        OpzioniRicerca {
+            limite: 10,
+            profondita_massima: 50,
+            controllo_cicli: false,
+            modalita: None,
+            espansioni_massime: 1_000_000,
+            euristica: Euristica::Zero,
+            landmarks: Vec::new(),
+            file_euristica: None,
+            coordinate: None,
+            scala_coordinate: 1.0,
+        }
+    }
+}
+
+/// Opzioni di lettura dei dataset di testo, comuni alla ricerca e ai sottocomandi
+#[derive(clap::Args, Debug)]
+pub struct OpzioniGrafo {
+    /// Il grafo è diretto, indipendentemente dall'intestazione del dataset
+    #[arg(long = "directed", action, conflicts_with = "non_diretto")]
+    pub diretto: bool,
+
+    /// Il grafo non è diretto, indipendentemente dall'intestazione del dataset
+    #[arg(long = "undirected", action)]
+    pub non_diretto: bool,
+
+    /// Il grafo è pesato: il costo delle azioni è letto da --weight-col
+    #[arg(long = "weighted", action)]
+    pub pesato: bool,
+
+    /// Colonna del nodo sorgente, contando da 0
+    #[arg(long = "src-col", default_value = "0")]
+    pub colonna_da: usize,
+
+    /// Colonna del nodo destinazione, contando da 0
+    #[arg(long = "dst-col", default_value = "1")]
+    pub colonna_a: usize,
+
+    /// Colonna del peso, contando da 0 (implica --weighted)
+    #[arg(long = "weight-col")]
+    pub colonna_peso: Option<usize>,
+
+    /// Separatore dei campi (virgole e spazi se omesso)
+    #[arg(long = "delimiter")]
+    pub separatore: Option<char>,
+
+    /// Formato del dataset (dedotto dall'estensione se omesso)
+    #[arg(long = "format")]
+    pub formato: Option<Formato>,
+
+    /// Costo degli archi pesati ripetuti nel dataset
+    #[arg(long = "duplicates", default_value = "keep-first")]
+    pub duplicati: Duplicati,
+
+    /// Thread che analizzano le liste di archi SNAP, 0 per usare tutti i core
+    #[arg(long = "threads", default_value = "1")]
+    pub thread: usize,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Comando {
+    /// Converte un dataset nel formato binario, che -F carica senza rileggere il testo
+    Convert {
+        /// Dataset da convertire (testo, gzip, bzip2 o zstd), "-" per lo standard input
+        input: String,
+
+        /// File binario da scrivere
+        output: String,
+
+        #[command(flatten)]
+        grafo: OpzioniGrafo,
+    },
+    /// Stampa il numero di nodi e archi, i gradi, le componenti connesse e il diametro stimato
+    Stats {
+        /// Dataset da analizzare, anche nel formato binario
+        input: String,
+
+        /// Stampa le statistiche in JSON, senza il riepilogo del caricamento
+        #[arg(long = "json", action)]
+        json: bool,
+
+        #[command(flatten)]
+        grafo: OpzioniGrafo,
+    },
+    /// Esporta il grafo in DOT, GraphML o lista di archi, colorando quanto visitato da una ricerca
+    Export {
+        /// Dataset da esportare, anche nel formato binario
+        input: String,
+
+        /// File da scrivere
+        output: String,
+
+        /// Formato del file scritto (dedotto dall'estensione se omesso: .dot, .gv, .graphml)
+        #[arg(long = "output-format")]
+        formato_output: Option<FormatoExport>,
+
+        /// Ricerca di cui colorare il cammino, gli stati espansi e la frontiera
+        #[arg(long = "search", requires_all = ["stato_iniziale", "stato_finale"])]
+        ricerca: Option<Ricerca>,
+
+        /// Nodo iniziale della ricerca, come compare nel dataset
+        #[arg(short = 'i', long = "inizia")]
+        stato_iniziale: Option<String>,
+
+        /// Nodo finale della ricerca, come compare nel dataset
+        #[arg(short = 'f', long = "finale")]
+        stato_finale: Option<String>,
+
+        /// Esporta solo i nodi entro questo numero di archi dal cammino trovato
+        #[arg(long = "radius", requires = "ricerca")]
+        raggio: Option<usize>,
+
+        #[command(flatten)]
+        opzioni: OpzioniRicerca,
+
+        #[command(flatten)]
+        grafo: OpzioniGrafo,
+    },
+}
+
+impl Default for OpzioniGrafo {
+    fn default() -> Self {
+        OpzioniGrafo {
+            diretto: false,
+            non_diretto: false,
+            pesato: false,
+            colonna_da: 0,
+            colonna_a: 1,
+            colonna_peso: None,
+            separatore: None,
+            formato: None,
+            duplicati: Duplicati::KeepFirst,
+            thread: 1,
+        }
+    }
+}
+
+impl OpzioniGrafo {
+    /// The graph flags override the header of the dataset, the type and the weights
+    /// independently: without `--weighted` or `--weight-col` the weights are guessed
+    pub fn opzioni_caricamento(&self) -> LoadOptions {
+        let tipo = if self.diretto {
+            Some(GraphType::Directed)
+        } else if self.non_diretto {
+            Some(GraphType::Undirected)
+        } else {
+            None
+        };
+        LoadOptions {
+            tipo,
+            pesato: if self.pesato || self.colonna_peso.is_some() {
+                Some(true)
+            } else {
+                None
+            },
+            colonna_da: self.colonna_da,
+            colonna_a: self.colonna_a,
+            colonna_peso: self.colonna_peso.unwrap_or(2),
+            separatore: self.separatore,
+            formato: self.formato,
+            duplicati: self.duplicati,
+            thread: match self.thread {
+                0 => thread::available_parallelism().map_or(1, |n| n.get()),
+                n => n,
+            },
+            riepilogo: true,
         }
     }
 }
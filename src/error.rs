@@ -0,0 +1,112 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use problem::compression::Compressione;
+
+/// Errors raised while loading a dataset or building a problem
+#[derive(Debug)]
+pub enum Error {
+    /// The file can't be opened or read
+    File { path: String, errore: io::Error },
+    /// The compressed stream is corrupted or truncated
+    Decompress {
+        path: String,
+        compressione: Compressione,
+        errore: io::Error,
+    },
+    /// The file is compressed with a format not enabled among the cargo features
+    UnsupportedCompression {
+        path: String,
+        compressione: Compressione,
+    },
+    /// A line that can't be parsed, `riga` is numbered from 1
+    MalformedLine {
+        path: String,
+        riga: usize,
+        contenuto: String,
+    },
+    /// A document (GraphML, JSON) that can't be parsed
+    InvalidDocument { path: String, messaggio: String },
+    /// An edge of a weighted graph given more than once, with `Duplicati::Error`
+    DuplicateEdge { da: String, a: String },
+    /// A state that isn't part of the graph, `num_stati` is the number of states of the graph
+    StateOutOfRange { stato: u32, num_stati: usize },
+    /// A node label that doesn't appear in the dataset
+    UnknownLabel(String),
+    /// A state of the graph without any action from or to it
+    UnreachableState(u32),
+    /// A file that starts like the binary format but can't be loaded
+    BinaryFormat { path: String, messaggio: String },
+    /// The parameters don't describe a valid instance of one of the example problems
+    InvalidProblem(String),
+}
+
+impl Error {
+    /// Process exit code: 2 is left to the command line errors
+    pub fn codice_uscita(&self) -> i32 {
+        match self {
+            Error::File { .. } => 3,
+            Error::Decompress { .. } => 4,
+            Error::MalformedLine { .. }
+            | Error::InvalidDocument { .. }
+            | Error::DuplicateEdge { .. } => 5,
+            Error::StateOutOfRange { .. } | Error::UnknownLabel(_) => 6,
+            Error::UnreachableState(_) => 7,
+            Error::InvalidProblem(_) => 8,
+            Error::UnsupportedCompression { .. } => 9,
+            Error::BinaryFormat { .. } => 10,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::File { path, errore } => write!(f, "impossibile leggere {}: {}", path, errore),
+            Error::Decompress {
+                path,
+                compressione,
+                errore,
+            } => write!(f, "file {} corrotto {}: {}", compressione, path, errore),
+            Error::UnsupportedCompression { path, compressione } => write!(
+                f,
+                "{}: compressione {} non abilitata, ricompilare con --features {}",
+                path, compressione, compressione
+            ),
+            Error::MalformedLine {
+                path,
+                riga,
+                contenuto,
+            } => write!(f, "riga {} di {} non valida: {:?}", riga, path, contenuto),
+            Error::InvalidDocument { path, messaggio } => {
+                write!(f, "documento {} non valido: {}", path, messaggio)
+            }
+            Error::DuplicateEdge { da, a } => write!(f, "arco {} -> {} duplicato", da, a),
+            Error::StateOutOfRange { stato, num_stati } => write!(
+                f,
+                "lo stato {} non esiste, il grafo ha {} stati",
+                stato, num_stati
+            ),
+            Error::UnknownLabel(etichetta) => {
+                write!(f, "il nodo {} non è presente nel dataset", etichetta)
+            }
+            Error::UnreachableState(stato) => {
+                write!(f, "lo stato {} non ha azioni da o verso altri stati", stato)
+            }
+            Error::BinaryFormat { path, messaggio } => {
+                write!(f, "file binario {} non valido: {}", path, messaggio)
+            }
+            Error::InvalidProblem(messaggio) => write!(f, "{}", messaggio),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::File { errore, .. } | Error::Decompress { errore, .. } => Some(errore),
+            _ => None,
+        }
+    }
+}
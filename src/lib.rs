@@ -0,0 +1,28 @@
+//! Search algorithms on graphs loaded from the SNAP datasets and on the example problems.
+//!
+//! A graph is loaded with [`Graph::from_file`] or wrapped in a [`Problem`] together with the
+//! initial and final states; any type implementing [`SearchProblem`] can then be solved by the
+//! strategies of [`Search`], which return a [`SearchResult`] with the [`SearchStats`] of the run.
+#[cfg(feature = "bzip2")]
+extern crate bzip2;
+extern crate clap;
+extern crate crc32fast;
+extern crate flate2;
+extern crate memmap2;
+extern crate rand;
+extern crate roxmltree;
+extern crate serde_json;
+#[cfg(feature = "zstd")]
+extern crate zstd;
+pub mod error;
+mod macros;
+pub mod problem;
+pub mod problems;
+
+pub use error::Error;
+pub use problem::graph::Graph;
+pub use problem::heuristic::Heuristic;
+pub use problem::node::{Action, State};
+pub use problem::search::{Search, SearchStats};
+pub use problem::solution::Solution;
+pub use problem::{Problem, Ricerca, SearchProblem, SearchResult};
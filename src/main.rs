@@ -1,80 +1,379 @@
 extern crate clap;
-extern crate flate2;
-extern crate rand;
+#[macro_use]
+extern crate eia;
 mod args;
-mod macros;
-mod problem;
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 
+use std::fmt::Display;
 use std::io::Write;
+use std::process;
 
 use args::*;
-use problem::*;
+use eia::problem::binary;
+use eia::problem::export::{self, *};
+use eia::problem::formats::dimacs::leggi_coordinate;
+use eia::problem::graph::*;
+use eia::problem::heuristic::*;
+use eia::problem::search::*;
+use eia::problem::stats::GraphStats;
+use eia::problem::*;
+use eia::problems::maze::*;
+use eia::problems::puzzle::*;
+use eia::problems::queens::*;
+use eia::problems::romania::*;
+use eia::problems::Dominio;
+use eia::Error;
+
+type Strategia<'a, P> =
+    fn(&mut Search<'a, P>) -> (SearchResult<<P as SearchProblem>::State>, SearchStats);
 
 fn main() {
     let args = Args::parse();
-    let mut problema = Problem::new(
-        args.stato_iniziale.unwrap(),
-        args.stato_finale.unwrap(),
-        &args.file.unwrap(),
-    );
-    #[rustfmt::skip]
-    let mut to_run: Vec<(Ricerca, fn(&mut Problem) -> SearchResult, bool)> = vec![
-        (Ricerca::TreeSearch, Problem::tree_search, false),
-        (Ricerca::BreadthFirst, Problem::breadth_first_search, false),
-        (Ricerca::UniformCost, Problem::uniform_cost_search, false),
-        (Ricerca::DepthLimited, Problem::depth_limited_search, false),
-        (Ricerca::IterativeDeepening, Problem::iterative_deepening_search, false),
-        (Ricerca::BiDirectional, Problem::bi_directional_search, false),
-    ];
-    if args.all {
-        // disable TreeSearch because it's too slow
-        for i in 1..to_run.len() {
-            to_run[i].2 = true;
+    if let Err(errore) = avvia(&args) {
+        eprintln!("Errore: {}", errore);
+        process::exit(errore.codice_uscita());
+    }
+}
+
+/// Builds the selected problem and runs the strategies on it
+fn avvia(args: &Args) -> Result<(), Error> {
+    if let Some(Comando::Convert {
+        input,
+        output,
+        grafo,
+    }) = &args.comando
+    {
+        let grafo = Graph::from_file_with_options(input, &grafo.opzioni_caricamento())?;
+        binary::scrivi(&grafo, output)?;
+        println!("Grafo salvato in {}", output);
+        return Ok(());
+    }
+    if let Some(Comando::Stats { input, json, grafo }) = &args.comando {
+        let opzioni = LoadOptions {
+            riepilogo: !json,
+            ..grafo.opzioni_caricamento()
+        };
+        let statistiche = GraphStats::new(&Graph::from_file_with_options(input, &opzioni)?);
+        if *json {
+            println!("{:#}", statistiche.to_json());
+        } else {
+            println!("{}", statistiche);
         }
-    } else if let Some(ricerca) = args.ricerca {
-        to_run.iter_mut().find(|(r, _, _)| *r == ricerca).unwrap().2 = true;
+        return Ok(());
+    }
+    if let Some(Comando::Export {
+        input,
+        output,
+        formato_output,
+        ricerca,
+        stato_iniziale,
+        stato_finale,
+        raggio,
+        opzioni,
+        grafo,
+    }) = &args.comando
+    {
+        let grafo = Graph::from_file_with_options(input, &grafo.opzioni_caricamento())?;
+        let formato = formato_output.unwrap_or_else(|| FormatoExport::da_estensione(output));
+        match (ricerca, stato_iniziale, stato_finale) {
+            (Some(ricerca), Some(iniziale), Some(finale)) => {
+                let problema =
+                    Problem::from_graph(grafo.stato(iniziale)?, grafo.stato(finale)?, grafo)?;
+                let euristica = euristica_grafo(&problema, opzioni)?;
+                let export = esplora(&problema, euristica, opzioni, ricerca, *raggio);
+                export::scrivi(problema.grafo(), output, formato, &export)?;
+            }
+            _ => export::scrivi(&grafo, output, formato, &OpzioniExport::default())?,
+        }
+        println!("Grafo esportato in {}", output);
+        return Ok(());
+    }
+    match args.dominio {
+        Dominio::Graph => {
+            let grafo = Graph::from_file_with_options(
+                args.file.as_ref().unwrap(),
+                &args.grafo.opzioni_caricamento(),
+            )?;
+            let problema = Problem::from_graph(
+                grafo.stato(args.stato_iniziale.as_ref().unwrap())?,
+                grafo.stato(args.stato_finale.as_ref().unwrap())?,
+                grafo,
+            )?;
+            let euristica = euristica_grafo(&problema, &args.opzioni)?;
+            esegui(&problema, euristica, args);
+        }
+        Dominio::Puzzle => {
+            let problema = SlidingPuzzle::new(args.tessere.clone())?;
+            let euristica: Box<dyn Heuristic<Tiles>> = match args.opzioni.euristica {
+                Euristica::Zero => Box::new(ZeroHeuristic),
+                Euristica::Manhattan => Box::new(ManhattanTiles),
+                Euristica::Misplaced => Box::new(MisplacedTiles),
+                _ => euristica_non_disponibile(&args.opzioni.euristica, &args.dominio),
+            };
+            esegui(&problema, euristica, args);
+        }
+        Dominio::Queens => {
+            let problema = NQueens::new(args.regine);
+            let euristica: Box<dyn Heuristic<Queens>> = match args.opzioni.euristica {
+                Euristica::Zero => Box::new(ZeroHeuristic),
+                _ => euristica_non_disponibile(&args.opzioni.euristica, &args.dominio),
+            };
+            esegui(&problema, euristica, args);
+        }
+        Dominio::Maze => {
+            let problema = GridMaze::from_file(args.labirinto.as_ref().unwrap())?;
+            let euristica: Box<dyn Heuristic<Cell>> = match args.opzioni.euristica {
+                Euristica::Zero => Box::new(ZeroHeuristic),
+                Euristica::Manhattan => Box::new(ManhattanCells {
+                    fine: problema.stato_finale().unwrap(),
+                }),
+                _ => euristica_non_disponibile(&args.opzioni.euristica, &args.dominio),
+            };
+            esegui(&problema, euristica, args);
+        }
+        Dominio::Romania => {
+            let problema = RomaniaMap::new(&args.partenza, &args.arrivo)?;
+            let euristica: Box<dyn Heuristic<City>> = match args.opzioni.euristica {
+                Euristica::Zero => Box::new(ZeroHeuristic),
+                Euristica::StraightLine if problema.arrivo() == "Bucharest" => {
+                    Box::new(StraightLine)
+                }
+                _ => euristica_non_disponibile(&args.opzioni.euristica, &args.dominio),
+            };
+            esegui(&problema, euristica, args);
+        }
+    }
+    Ok(())
+}
+
+/// Heuristic of a graph problem selected by the search options
+fn euristica_grafo<'a>(
+    problema: &'a Problem,
+    opzioni: &OpzioniRicerca,
+) -> Result<Box<dyn Heuristic + 'a>, Error> {
+    let euristica: Box<dyn Heuristic + 'a> = match opzioni.euristica {
+        Euristica::Zero => Box::new(ZeroHeuristic),
+        Euristica::Landmark if opzioni.landmarks.is_empty() => Box::new(
+            LandmarkHeuristic::casuali(problema.grafo(), 4, problema.get_stato_finale()),
+        ),
+        Euristica::Landmark => {
+            let landmarks = opzioni
+                .landmarks
+                .iter()
+                .map(|l| problema.grafo().stato(l))
+                .collect::<Result<Vec<_>, Error>>()?;
+            Box::new(LandmarkHeuristic::new(
+                problema.grafo(),
+                &landmarks,
+                problema.get_stato_finale(),
+            )?)
+        }
+        Euristica::File => Box::new(FileHeuristic::from_file(
+            opzioni.file_euristica.as_ref().unwrap(),
+            problema.grafo(),
+        )?),
+        Euristica::Euclidean => Box::new(EuclideanHeuristic {
+            coordinate: leggi_coordinate(opzioni.coordinate.as_ref().unwrap(), problema.grafo())?,
+            scala: opzioni.scala_coordinate,
+            stato_finale: problema.get_stato_finale(),
+        }),
+        _ => euristica_non_disponibile(&opzioni.euristica, &Dominio::Graph),
+    };
+    Ok(euristica)
+}
+
+/// Applies the search options to `ricercatore`
+fn configura<'a, P: SearchProblem>(ricercatore: &mut Search<'a, P>, opzioni: &OpzioniRicerca) {
+    ricercatore.set_limite(opzioni.limite);
+    ricercatore.set_profondita_massima(opzioni.profondita_massima);
+    ricercatore.set_controllo_cicli(opzioni.controllo_cicli);
+    ricercatore.set_espansioni_massime(opzioni.espansioni_massime);
+    if let Some(modalita) = opzioni.modalita {
+        ricercatore.set_modalita(modalita);
+    }
+}
+
+/// Runs `ricerca` on `problema` and keeps what it met, for the export; with `raggio` only the
+/// states around the solution, or around the initial and final states when there is none,
+/// are exported
+fn esplora<'a>(
+    problema: &'a Problem,
+    euristica: Box<dyn Heuristic + 'a>,
+    opzioni_ricerca: &OpzioniRicerca,
+    ricerca: &Ricerca,
+    raggio: Option<usize>,
+) -> OpzioniExport {
+    let mut ricercatore = Search::new(problema);
+    ricercatore.set_euristica(euristica);
+    configura(&mut ricercatore, opzioni_ricerca);
+    let (_, funzione) = strategie().into_iter().find(|(r, _)| r == ricerca).unwrap();
+    let (risultato, _) = funzione(&mut ricercatore);
+    let mut opzioni = OpzioniExport {
+        esplorazione: ricercatore.esplorazione(),
+        ..OpzioniExport::default()
+    };
+    match risultato {
+        SearchResult::Found(soluzione) => {
+            println!("{}: cammino di {} passi", ricerca, soluzione.profondita());
+            opzioni.cammino = soluzione.stati;
+        }
+        SearchResult::BudgetExceeded => println!(
+            "{}: interrotta dopo {} espansioni (--max-expansions)",
+            ricerca, opzioni_ricerca.espansioni_massime
+        ),
+        _ => println!("{}: nessun cammino trovato", ricerca),
     }
     println!(
-        "Inizio ricerca da: {} verso: {}",
-        problema.get_stato_iniziale(),
-        problema.get_stato_finale()
+        "Stati espansi: {}, nella frontiera: {}",
+        opzioni.esplorazione.espansi.len(),
+        opzioni.esplorazione.frontiera.len()
+    );
+    if let Some(raggio) = raggio {
+        let centro = if opzioni.cammino.is_empty() {
+            vec![problema.stato_iniziale(), problema.get_stato_finale()]
+        } else {
+            opzioni.cammino.clone()
+        };
+        opzioni.stati = Some(intorno(problema.grafo(), &centro, raggio));
+    }
+    opzioni
+}
+
+/// Every strategy of `Search`, in the order of the results table
+#[rustfmt::skip]
+fn strategie<'a, P: SearchProblem>() -> Vec<(Ricerca, Strategia<'a, P>)> {
+    vec![
+        (Ricerca::TreeSearch, Search::tree_search),
+        (Ricerca::BreadthFirst, Search::breadth_first_search),
+        (Ricerca::UniformCost, Search::uniform_cost_search),
+        (Ricerca::DepthLimited, Search::depth_limited_search),
+        (Ricerca::IterativeDeepening, Search::iterative_deepening_search),
+        (Ricerca::BiDirectional, Search::bi_directional_search),
+        (Ricerca::AStar, Search::a_star_search),
+        (Ricerca::GreedyBestFirst, Search::greedy_best_first_search),
+        (Ricerca::BellmanFord, Search::bellman_ford_search),
+        (Ricerca::IdaStar, Search::ida_star_search),
+        (Ricerca::RecursiveBestFirst, Search::recursive_best_first_search),
+    ]
+}
+
+fn euristica_non_disponibile(euristica: &Euristica, dominio: &Dominio) -> ! {
+    eprintln!(
+        "Euristica {} non disponibile per il problema {}",
+        euristica.to_possible_value().unwrap().get_name(),
+        dominio.to_possible_value().unwrap().get_name()
     );
+    process::exit(2);
+}
+
+/// Runs the selected strategies on `problema` and prints the results table
+fn esegui<'a, P>(problema: &'a P, euristica: Box<dyn Heuristic<P::State> + 'a>, args: &Args)
+where
+    P: SearchProblem,
+    P::State: Display,
+{
+    let mut ricercatore = Search::new(problema);
+    ricercatore.set_euristica(euristica);
+    configura(&mut ricercatore, &args.opzioni);
+    let mut to_run: Vec<(Ricerca, Strategia<'a, P>, bool)> = strategie()
+        .into_iter()
+        .map(|(ricerca, funzione)| (ricerca, funzione, false))
+        .collect();
+    if args.all {
+        // the tree searches are stopped by the expansion budget, so every strategy can run
+        for r in to_run.iter_mut() {
+            r.2 = true;
+        }
+    } else if let Some(ricerca) = &args.ricerca {
+        to_run.iter_mut().find(|(r, _, _)| r == ricerca).unwrap().2 = true;
+    }
+    match problema.stato_finale() {
+        Some(stato_finale) => println!(
+            "Inizio ricerca da: {} verso: {}",
+            problema.etichetta(&problema.stato_iniziale()),
+            problema.etichetta(&stato_finale)
+        ),
+        None => println!(
+            "Inizio ricerca da: {}",
+            problema.etichetta(&problema.stato_iniziale())
+        ),
+    }
     println!(
-        "\x1b[1m{:^20}|{:^11}|{:^7}|{:^7}|{:^11}\x1b[0m",
-        "Algoritmo", "Risultato", "Depth", "Costo", "Tempo"
+        "\x1b[1m{:^20}|{:^11}|{:^7}|{:^7}|{:^11}|{:^10}|{:^10}|{:^10}|{:^10}|{:^10}|{:^6}\x1b[0m",
+        "Algoritmo",
+        "Risultato",
+        "Depth",
+        "Costo",
+        "Tempo",
+        "Espansi",
+        "Generati",
+        "Duplicati",
+        "Frontiera",
+        "Raggiunti",
+        "Iter."
     );
     for (ricerca, funzione, to_run) in to_run {
         if to_run {
             print!("{:<20}|", ricerca.to_string());
             std::io::stdout().flush().unwrap();
+            let (result, statistiche);
             let elapsed = timed_run!({
-                let result = funzione(&mut problema);
-                print!(
-                    "{:^11}|{:>7}|{:>7}|",
-                    match result {
-                        SearchResult::Found(_) => "Trovato",
-                        SearchResult::Failure => "Fallito",
-                        SearchResult::CutOff => "Cutoff",
-                    },
-                    if let SearchResult::Found(nodo) = &result {
-                        nodo.profondita
-                    } else {
-                        0
-                    },
-                    if let SearchResult::Found(nodo) = &result {
-                        nodo.costo_cammino
-                    } else {
-                        0
-                    }
-                );
+                (result, statistiche) = funzione(&mut ricercatore);
             });
             println!(
-                "{:>4}.{:05}s",
+                "{:^11}|{:>7}|{:>7}|{:>4}.{:05}s|{:>10}|{:>10}|{:>10}|{:>10}|{:>10}|{:>6}",
+                match result {
+                    SearchResult::Found(_) => "Trovato",
+                    SearchResult::Failure => "Fallito",
+                    SearchResult::CutOff => "Cutoff",
+                    SearchResult::NegativeCost => "Costi neg.",
+                    SearchResult::NegativeCycle(_) => "Ciclo neg.",
+                    SearchResult::Unsupported => "N/D",
+                    SearchResult::BudgetExceeded => "Interrotto",
+                },
+                if let SearchResult::Found(soluzione) = &result {
+                    soluzione.profondita()
+                } else {
+                    0
+                },
+                if let SearchResult::Found(soluzione) = &result {
+                    soluzione.costo()
+                } else {
+                    0
+                },
                 elapsed.as_secs(),
-                elapsed.subsec_millis() + elapsed.subsec_micros()
+                elapsed.subsec_millis() + elapsed.subsec_micros(),
+                statistiche.espansi,
+                statistiche.generati,
+                statistiche.duplicati,
+                statistiche.frontiera_massima,
+                statistiche.raggiunti_massimi,
+                statistiche.iterazioni
             );
+            if let (true, SearchResult::Found(soluzione)) = (args.path, &result) {
+                println!("  Cammino: {}", soluzione.map(|s| problema.etichetta(s)));
+            }
+            if ricerca == Ricerca::IdaStar || ricerca == Ricerca::RecursiveBestFirst {
+                println!("  Riespansioni: {}", statistiche.riespansioni);
+            }
+            if let SearchResult::NegativeCycle(ciclo) = &result {
+                let ciclo: Vec<String> = ciclo.iter().map(|s| problema.etichetta(s)).collect();
+                println!("  Ciclo negativo: {}", ciclo.join(" -> "));
+            }
+            match result {
+                SearchResult::NegativeCost => {
+                    println!("  {} richiede azioni con costo non negativo", ricerca)
+                }
+                SearchResult::Unsupported => {
+                    println!("  {} non è applicabile a questo problema", ricerca)
+                }
+                SearchResult::BudgetExceeded => println!(
+                    "  {} ha espanso {} nodi senza trovare l'obiettivo (--max-expansions)",
+                    ricerca, args.opzioni.espansioni_massime
+                ),
+                _ => {}
+            }
         }
     }
 }
@@ -0,0 +1,334 @@
+//! Binary cache of a loaded graph, so that large datasets are parsed only once: the file is
+//! memory-mapped and the CSR arrays are used in place, without copying them.
+//!
+//! Every number is little-endian:
+//! - header: `MAGIC`, version (u32), flags (u32: 1 directed, 2 weighted), number of states (u64),
+//!   number of actions (u64), number of edges (u64)
+//! - CSR arrays: offsets (u64, states + 1), targets (u32, actions), costs (i32, actions)
+//! - id map: kind (u8: 0 numeric, 1 textual), then a u64 per state or a length (u32) and the
+//!   UTF-8 bytes of every label
+//! - CRC32 (u32) of everything before it
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::mem;
+use std::ops::Deref;
+use std::slice;
+use std::sync::Arc;
+
+use crc32fast::Hasher;
+use memmap2::Mmap;
+
+use error::*;
+use problem::graph::*;
+use problem::idmap::*;
+use problem::node::*;
+
+pub const MAGIC: &[u8; 8] = b"EIAGRAPH";
+pub const VERSIONE: u32 = 1;
+
+const DIRETTO: u32 = 1;
+const PESATO: u32 = 2;
+
+/// True when `path` starts with the magic bytes of the binary format
+pub fn is_binary(path: &str) -> bool {
+    let mut inizio = [0; 8];
+    match File::open(path) {
+        Ok(mut file) => file.read_exact(&mut inizio).is_ok() && &inizio == MAGIC,
+        Err(_) => false,
+    }
+}
+
+/// Writes `grafo` in the binary format at `path`
+pub fn scrivi(grafo: &Graph, path: &str) -> Result<(), Error> {
+    let errore_file = |errore| Error::File {
+        path: path.to_string(),
+        errore,
+    };
+    let mut file = Scrittore {
+        path,
+        file: BufWriter::new(File::create(path).map_err(errore_file)?),
+        crc: Hasher::new(),
+    };
+    let n = grafo.num_nodi() as State;
+    let azioni: usize = (0..n).map(|s| grafo.vicini(s).len()).sum();
+    let mut flag = 0;
+    if grafo.is_directed() {
+        flag |= DIRETTO;
+    }
+    if grafo.is_weighted() {
+        flag |= PESATO;
+    }
+    file.scrivi(MAGIC)?;
+    file.scrivi(&VERSIONE.to_le_bytes())?;
+    file.scrivi(&flag.to_le_bytes())?;
+    file.scrivi(&(n as u64).to_le_bytes())?;
+    file.scrivi(&(azioni as u64).to_le_bytes())?;
+    file.scrivi(&(grafo.num_archi() as u64).to_le_bytes())?;
+    let mut offset = 0u64;
+    file.scrivi(&offset.to_le_bytes())?;
+    for s in 0..n {
+        offset += grafo.vicini(s).len() as u64;
+        file.scrivi(&offset.to_le_bytes())?;
+    }
+    for s in 0..n {
+        for t in grafo.vicini(s) {
+            file.scrivi(&t.to_le_bytes())?;
+        }
+    }
+    for s in 0..n {
+        for c in grafo.costi(s) {
+            file.scrivi(&c.to_le_bytes())?;
+        }
+    }
+    match grafo.etichette() {
+        IdMap::Numeriche { etichette, .. } => {
+            file.scrivi(&[0])?;
+            for e in etichette {
+                file.scrivi(&e.to_le_bytes())?;
+            }
+        }
+        IdMap::Testuali { etichette, .. } => {
+            file.scrivi(&[1])?;
+            for e in etichette {
+                file.scrivi(&(e.len() as u32).to_le_bytes())?;
+                file.scrivi(e.as_bytes())?;
+            }
+        }
+    }
+    let crc = file.crc.clone().finalize();
+    file.file
+        .write_all(&crc.to_le_bytes())
+        .and_then(|_| file.file.flush())
+        .map_err(errore_file)
+}
+
+/// Loads a graph written by `scrivi`, checking the version and the checksum
+pub fn leggi(path: &str) -> Result<Graph, Error> {
+    let errore_file = |errore| Error::File {
+        path: path.to_string(),
+        errore,
+    };
+    let file = File::open(path).map_err(errore_file)?;
+    // SAFETY: the file must not change while it's mapped, the arrays of the graph read it in place
+    let mappa = Arc::new(unsafe { Mmap::map(&file) }.map_err(errore_file)?);
+    let dati: &[u8] = &mappa;
+    let non_valido = |messaggio: &str| Error::BinaryFormat {
+        path: path.to_string(),
+        messaggio: messaggio.to_string(),
+    };
+    if dati.len() < MAGIC.len() + 4 || &dati[..MAGIC.len()] != MAGIC {
+        return Err(non_valido("intestazione mancante"));
+    }
+    let (contenuto, crc) = dati.split_at(dati.len() - 4);
+    if crc32fast::hash(contenuto).to_le_bytes() != crc {
+        return Err(non_valido("checksum errato"));
+    }
+    let mut lettore = Lettore {
+        dati: contenuto,
+        posizione: MAGIC.len(),
+    };
+    let troncato = || non_valido("file troncato");
+    let versione = lettore.u32().ok_or_else(troncato)?;
+    if versione != VERSIONE {
+        return Err(non_valido(&format!(
+            "versione {} non supportata, attesa {}",
+            versione, VERSIONE
+        )));
+    }
+    let flag = lettore.u32().ok_or_else(troncato)?;
+    let n = lettore.dimensione().ok_or_else(troncato)?;
+    let azioni = lettore.dimensione().ok_or_else(troncato)?;
+    let archi = lettore.dimensione().ok_or_else(troncato)?;
+    let offsets: Array<usize> = lettore
+        .array(&mappa, n.checked_add(1).ok_or_else(troncato)?)
+        .ok_or_else(troncato)?;
+    let targets: Array<State> = lettore.array(&mappa, azioni).ok_or_else(troncato)?;
+    let costi: Array<i32> = lettore.array(&mappa, azioni).ok_or_else(troncato)?;
+    if offsets.first() != Some(&0)
+        || offsets.windows(2).any(|o| o[0] > o[1])
+        || offsets.last() != Some(&azioni)
+        || targets.iter().any(|&t| t as usize >= n)
+    {
+        return Err(non_valido("array CSR incoerenti"));
+    }
+    let etichette = match lettore.blocco(1, 1).ok_or_else(troncato)?[0] {
+        0 => {
+            let etichette: Vec<u64> = lettore
+                .blocco(n, 8)
+                .ok_or_else(troncato)?
+                .chunks_exact(8)
+                .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                .collect();
+            let stati: HashMap<u64, State> = etichette
+                .iter()
+                .enumerate()
+                .map(|(s, &e)| (e, s as State))
+                .collect();
+            IdMap::Numeriche { etichette, stati }
+        }
+        1 => {
+            // every label takes at least its length, a bigger n is caught as truncated
+            let mut etichette = Vec::with_capacity(n.min(contenuto.len() / 4));
+            for _ in 0..n {
+                let lunghezza = lettore.u32().ok_or_else(troncato)? as usize;
+                let byte = lettore.blocco(lunghezza, 1).ok_or_else(troncato)?;
+                let etichetta = String::from_utf8(byte.to_vec())
+                    .map_err(|_| non_valido("etichetta non UTF-8"))?;
+                etichette.push(etichetta);
+            }
+            let stati: HashMap<String, State> = etichette
+                .iter()
+                .enumerate()
+                .map(|(s, e)| (e.clone(), s as State))
+                .collect();
+            IdMap::Testuali { etichette, stati }
+        }
+        _ => return Err(non_valido("tipo di etichette sconosciuto")),
+    };
+    if lettore.posizione != contenuto.len() {
+        return Err(non_valido("dati in eccesso"));
+    }
+    let gtype = if flag & DIRETTO != 0 {
+        GraphType::Directed
+    } else {
+        GraphType::Undirected
+    };
+    Ok(Graph::from_csr(
+        gtype,
+        flag & PESATO != 0,
+        etichette,
+        offsets,
+        targets,
+        costi,
+        archi,
+    ))
+}
+
+/// Buffered writer that keeps the CRC32 of what it writes
+struct Scrittore<'a> {
+    path: &'a str,
+    file: BufWriter<File>,
+    crc: Hasher,
+}
+
+impl<'a> Scrittore<'a> {
+    fn scrivi(&mut self, byte: &[u8]) -> Result<(), Error> {
+        self.crc.update(byte);
+        self.file.write_all(byte).map_err(|errore| Error::File {
+            path: self.path.to_string(),
+            errore,
+        })
+    }
+}
+
+/// Array of the CSR form of a graph, built in memory or read in place from a mapped file
+pub(crate) struct Array<T>(Dati<T>);
+
+enum Dati<T> {
+    Proprio(Vec<T>),
+    Mappato {
+        mappa: Arc<Mmap>,
+        inizio: usize,
+        len: usize,
+    },
+}
+
+impl<T> From<Vec<T>> for Array<T> {
+    fn from(dati: Vec<T>) -> Self {
+        Array(Dati::Proprio(dati))
+    }
+}
+
+impl<T> Deref for Array<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        match &self.0 {
+            Dati::Proprio(dati) => dati,
+            // SAFETY: `Lettore::array` only maps aligned blocks of `len` elements of an
+            // `Elemento` within the mapping, and every bit pattern is a valid `Elemento`
+            Dati::Mappato { mappa, inizio, len } => unsafe {
+                slice::from_raw_parts(mappa.as_ptr().add(*inizio) as *const T, *len)
+            },
+        }
+    }
+}
+
+/// Number in the CSR arrays of the file, stored little-endian in `DIMENSIONE` bytes
+trait Elemento: Copy {
+    const DIMENSIONE: usize;
+    fn da_le(byte: &[u8]) -> Self;
+}
+
+impl Elemento for usize {
+    const DIMENSIONE: usize = 8;
+    fn da_le(byte: &[u8]) -> Self {
+        u64::from_le_bytes(byte.try_into().unwrap()) as usize
+    }
+}
+
+impl Elemento for u32 {
+    const DIMENSIONE: usize = 4;
+    fn da_le(byte: &[u8]) -> Self {
+        u32::from_le_bytes(byte.try_into().unwrap())
+    }
+}
+
+impl Elemento for i32 {
+    const DIMENSIONE: usize = 4;
+    fn da_le(byte: &[u8]) -> Self {
+        i32::from_le_bytes(byte.try_into().unwrap())
+    }
+}
+
+/// Sequential reader over the mapped file, `None` when the data ends early
+struct Lettore<'a> {
+    dati: &'a [u8],
+    posizione: usize,
+}
+
+impl<'a> Lettore<'a> {
+    fn blocco(&mut self, quanti: usize, dimensione: usize) -> Option<&'a [u8]> {
+        let fine = quanti
+            .checked_mul(dimensione)?
+            .checked_add(self.posizione)?;
+        let blocco = self.dati.get(self.posizione..fine)?;
+        self.posizione = fine;
+        Some(blocco)
+    }
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.blocco(1, 4)?.try_into().unwrap()))
+    }
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.blocco(1, 8)?.try_into().unwrap()))
+    }
+    /// The next `quanti` elements, in place when the layout of `T` matches the file, copied
+    /// otherwise (big-endian or 32-bit targets)
+    fn array<T: Elemento>(&mut self, mappa: &Arc<Mmap>, quanti: usize) -> Option<Array<T>> {
+        let inizio = self.posizione;
+        let blocco = self.blocco(quanti, T::DIMENSIONE)?;
+        if cfg!(target_endian = "little")
+            && mem::size_of::<T>() == T::DIMENSIONE
+            && blocco.as_ptr().cast::<T>().is_aligned()
+        {
+            Some(Array(Dati::Mappato {
+                mappa: mappa.clone(),
+                inizio,
+                len: quanti,
+            }))
+        } else {
+            Some(
+                blocco
+                    .chunks_exact(T::DIMENSIONE)
+                    .map(T::da_le)
+                    .collect::<Vec<_>>()
+                    .into(),
+            )
+        }
+    }
+    /// A size of the header, `None` when it doesn't fit in a `usize`
+    fn dimensione(&mut self) -> Option<usize> {
+        self.u64()?.try_into().ok()
+    }
+}
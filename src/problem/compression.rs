@@ -0,0 +1,102 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+
+use error::*;
+
+/// Path that reads the dataset from the standard input
+pub const STDIN: &str = "-";
+
+/// Compression of a dataset file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compressione {
+    Nessuna,
+    Gzip,
+    Bzip2,
+    Zstd,
+}
+
+impl fmt::Display for Compressione {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compressione::Nessuna => write!(f, "testo"),
+            Compressione::Gzip => write!(f, "gzip"),
+            Compressione::Bzip2 => write!(f, "bzip2"),
+            Compressione::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl Compressione {
+    /// Recognizes the compressed streams from their first bytes
+    pub fn da_magic(inizio: &[u8]) -> Option<Compressione> {
+        if inizio.starts_with(&[0x1f, 0x8b]) {
+            Some(Compressione::Gzip)
+        } else if inizio.starts_with(b"BZh") {
+            Some(Compressione::Bzip2)
+        } else if inizio.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Compressione::Zstd)
+        } else {
+            None
+        }
+    }
+    pub fn da_estensione(path: &str) -> Option<Compressione> {
+        match Path::new(path).extension()?.to_str()? {
+            "gz" => Some(Compressione::Gzip),
+            "bz2" => Some(Compressione::Bzip2),
+            "zst" | "zstd" => Some(Compressione::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Opens the dataset at `path`, or the standard input when `path` is `-`, and decompresses it.
+/// The magic bytes decide the compression; a file without them is read as plain text unless
+/// its extension names a compressor, so that a damaged header is reported as an error.
+pub fn apri(path: &str) -> Result<(Box<dyn BufRead + Send>, Compressione), Error> {
+    let errore_file = |errore| Error::File {
+        path: path.to_string(),
+        errore,
+    };
+    let mut sorgente: Box<dyn Read + Send> = if path == STDIN {
+        Box::new(io::stdin())
+    } else {
+        Box::new(File::open(path).map_err(errore_file)?)
+    };
+    // the magic bytes are read and then put back in front of the stream
+    let mut magic = Vec::with_capacity(4);
+    sorgente
+        .by_ref()
+        .take(4)
+        .read_to_end(&mut magic)
+        .map_err(errore_file)?;
+    let compressione = Compressione::da_magic(&magic)
+        .or_else(|| Compressione::da_estensione(path))
+        .unwrap_or(Compressione::Nessuna);
+    let flusso = Cursor::new(magic).chain(sorgente);
+    let lettore: Box<dyn BufRead + Send> = match compressione {
+        Compressione::Nessuna => Box::new(BufReader::new(flusso)),
+        Compressione::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(flusso))),
+        #[cfg(feature = "bzip2")]
+        Compressione::Bzip2 => Box::new(BufReader::new(::bzip2::read::MultiBzDecoder::new(flusso))),
+        #[cfg(feature = "zstd")]
+        Compressione::Zstd => Box::new(BufReader::new(::zstd::Decoder::new(flusso).map_err(
+            |errore| Error::Decompress {
+                path: path.to_string(),
+                compressione,
+                errore,
+            },
+        )?)),
+        #[allow(unreachable_patterns)]
+        _ => {
+            return Err(Error::UnsupportedCompression {
+                path: path.to_string(),
+                compressione,
+            })
+        }
+    };
+    Ok((lettore, compressione))
+}
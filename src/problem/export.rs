@@ -0,0 +1,304 @@
+//! Writes a graph, or the part of it around a search, as DOT, GraphML or edge list.
+//! DOT and GraphML also show what a search has met: the states of the solution, the
+//! expanded states and the frontier are colored, as the actions along the solution.
+use std::collections::{HashSet, VecDeque};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use error::*;
+use problem::graph::*;
+use problem::node::*;
+use problem::search::Esplorazione;
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum FormatoExport {
+    Dot,
+    Graphml,
+    /// SNAP edge list, that `-F` reads back with its type and weights; the states without
+    /// edges are not written, so they are lost
+    Edgelist,
+}
+
+impl FormatoExport {
+    /// ".dot"/".gv" and ".graphml" files, every other file is an edge list
+    pub fn da_estensione(path: &str) -> FormatoExport {
+        match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("dot") | Some("gv") => FormatoExport::Dot,
+            Some("graphml") => FormatoExport::Graphml,
+            _ => FormatoExport::Edgelist,
+        }
+    }
+}
+
+/// What is exported besides the graph; the default is the whole graph without colors
+#[derive(Debug, Clone, Default)]
+pub struct OpzioniExport {
+    /// Only the subgraph induced by these states is written
+    pub stati: Option<HashSet<State>>,
+    /// States of the solution, from the initial to the final one
+    pub cammino: Vec<State>,
+    pub esplorazione: Esplorazione,
+}
+
+/// How a state was met by the search, in order of precedence
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ruolo {
+    Cammino,
+    Frontiera,
+    Espanso,
+    Nessuno,
+}
+
+impl Ruolo {
+    fn nome(self) -> &'static str {
+        match self {
+            Ruolo::Cammino => "path",
+            Ruolo::Frontiera => "frontier",
+            Ruolo::Espanso => "expanded",
+            Ruolo::Nessuno => "none",
+        }
+    }
+    fn colore(self) -> Option<&'static str> {
+        match self {
+            Ruolo::Cammino => Some("red"),
+            Ruolo::Frontiera => Some("orange"),
+            Ruolo::Espanso => Some("lightblue"),
+            Ruolo::Nessuno => None,
+        }
+    }
+}
+
+/// States within `raggio` actions from any of `centro`, following the actions in both directions
+pub fn intorno(grafo: &Graph, centro: &[State], raggio: usize) -> HashSet<State> {
+    let mut stati: HashSet<State> = centro.iter().cloned().collect();
+    let mut coda: VecDeque<(State, usize)> = centro.iter().map(|&s| (s, 0)).collect();
+    while let Some((s, distanza)) = coda.pop_front() {
+        if distanza == raggio {
+            continue;
+        }
+        let vicini = grafo
+            .azioni(s)
+            .chain(grafo.azioni_inverse(s))
+            .map(|a| a.risultato);
+        for t in vicini {
+            if stati.insert(t) {
+                coda.push_back((t, distanza + 1));
+            }
+        }
+    }
+    stati
+}
+
+/// Writes the export of `grafo` at `path`
+pub fn scrivi(
+    grafo: &Graph,
+    path: &str,
+    formato: FormatoExport,
+    opzioni: &OpzioniExport,
+) -> Result<(), Error> {
+    let errore_file = |errore| Error::File {
+        path: path.to_string(),
+        errore,
+    };
+    let mut output = BufWriter::new(File::create(path).map_err(errore_file)?);
+    esporta(grafo, formato, opzioni, &mut output)
+        .and_then(|_| output.flush())
+        .map_err(errore_file)
+}
+
+/// Writes `grafo` in the given format; an undirected edge is written once
+pub fn esporta<W: Write>(
+    grafo: &Graph,
+    formato: FormatoExport,
+    opzioni: &OpzioniExport,
+    output: &mut W,
+) -> io::Result<()> {
+    let stati: Vec<State> = match &opzioni.stati {
+        Some(stati) => {
+            let mut stati: Vec<State> = stati.iter().cloned().collect();
+            stati.sort_unstable();
+            stati
+        }
+        None => (0..grafo.num_nodi() as State).collect(),
+    };
+    let incluso = |s: State| {
+        opzioni
+            .stati
+            .as_ref()
+            .is_none_or(|stati| stati.contains(&s))
+    };
+    let mut archi = Vec::new();
+    for &s in &stati {
+        for (&t, &costo) in grafo.vicini(s).iter().zip(grafo.costi(s)) {
+            if incluso(t) && (grafo.is_directed() || s <= t) {
+                archi.push((s, t, costo));
+            }
+        }
+    }
+    let passi: HashSet<(State, State)> = opzioni
+        .cammino
+        .windows(2)
+        .flat_map(|p| {
+            if grafo.is_directed() {
+                vec![(p[0], p[1])]
+            } else {
+                vec![(p[0], p[1]), (p[1], p[0])]
+            }
+        })
+        .collect();
+    let cammino: HashSet<State> = opzioni.cammino.iter().cloned().collect();
+    let ruolo = |s: State| {
+        if cammino.contains(&s) {
+            Ruolo::Cammino
+        } else if opzioni.esplorazione.frontiera.contains(&s) {
+            Ruolo::Frontiera
+        } else if opzioni.esplorazione.espansi.contains(&s) {
+            Ruolo::Espanso
+        } else {
+            Ruolo::Nessuno
+        }
+    };
+    match formato {
+        FormatoExport::Dot => {
+            let (tipo, freccia) = if grafo.is_directed() {
+                ("digraph", "->")
+            } else {
+                ("graph", "--")
+            };
+            writeln!(output, "{} eia {{", tipo)?;
+            for &s in &stati {
+                write!(output, "  \"{}\"", dot(&grafo.etichetta(s)))?;
+                match ruolo(s).colore() {
+                    Some(colore) => writeln!(output, " [style=filled, fillcolor={}];", colore)?,
+                    None => writeln!(output, ";")?,
+                }
+            }
+            for &(s, t, costo) in &archi {
+                let mut attributi = Vec::new();
+                if grafo.is_weighted() {
+                    attributi.push(format!("label={}", costo));
+                }
+                if passi.contains(&(s, t)) {
+                    attributi.push("color=red, penwidth=2".to_string());
+                }
+                write!(
+                    output,
+                    "  \"{}\" {} \"{}\"",
+                    dot(&grafo.etichetta(s)),
+                    freccia,
+                    dot(&grafo.etichetta(t))
+                )?;
+                if attributi.is_empty() {
+                    writeln!(output, ";")?;
+                } else {
+                    writeln!(output, " [{}];", attributi.join(", "))?;
+                }
+            }
+            writeln!(output, "}}")
+        }
+        FormatoExport::Graphml => {
+            writeln!(output, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+            writeln!(
+                output,
+                "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+            )?;
+            writeln!(
+                output,
+                "  <key id=\"ricerca\" for=\"node\" attr.name=\"search\" attr.type=\"string\"/>"
+            )?;
+            writeln!(
+                output,
+                "  <key id=\"colore\" for=\"all\" attr.name=\"color\" attr.type=\"string\"/>"
+            )?;
+            writeln!(
+                output,
+                "  <key id=\"cammino\" for=\"edge\" attr.name=\"path\" attr.type=\"boolean\"/>"
+            )?;
+            if grafo.is_weighted() {
+                writeln!(
+                    output,
+                    "  <key id=\"peso\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>"
+                )?;
+            }
+            writeln!(
+                output,
+                "  <graph edgedefault=\"{}\">",
+                if grafo.is_directed() {
+                    "directed"
+                } else {
+                    "undirected"
+                }
+            )?;
+            for &s in &stati {
+                let ruolo = ruolo(s);
+                write!(output, "    <node id=\"{}\">", xml(&grafo.etichetta(s)))?;
+                write!(output, "<data key=\"ricerca\">{}</data>", ruolo.nome())?;
+                if let Some(colore) = ruolo.colore() {
+                    write!(output, "<data key=\"colore\">{}</data>", colore)?;
+                }
+                writeln!(output, "</node>")?;
+            }
+            for &(s, t, costo) in &archi {
+                write!(
+                    output,
+                    "    <edge source=\"{}\" target=\"{}\">",
+                    xml(&grafo.etichetta(s)),
+                    xml(&grafo.etichetta(t))
+                )?;
+                if grafo.is_weighted() {
+                    write!(output, "<data key=\"peso\">{}</data>", costo)?;
+                }
+                if passi.contains(&(s, t)) {
+                    write!(output, "<data key=\"cammino\">true</data>")?;
+                    write!(output, "<data key=\"colore\">red</data>")?;
+                }
+                writeln!(output, "</edge>")?;
+            }
+            writeln!(output, "  </graph>")?;
+            writeln!(output, "</graphml>")
+        }
+        FormatoExport::Edgelist => {
+            writeln!(
+                output,
+                "# {} graph, {}",
+                grafo.gtype(),
+                if grafo.is_weighted() {
+                    "weighted"
+                } else {
+                    "unweighted"
+                }
+            )?;
+            for &(s, t, costo) in &archi {
+                if grafo.is_weighted() {
+                    writeln!(
+                        output,
+                        "{}\t{}\t{}",
+                        grafo.etichetta(s),
+                        grafo.etichetta(t),
+                        costo
+                    )?;
+                } else {
+                    writeln!(output, "{}\t{}", grafo.etichetta(s), grafo.etichetta(t))?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// `etichetta` escaped for a quoted DOT id
+fn dot(etichetta: &str) -> String {
+    etichetta.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// `etichetta` escaped for an XML attribute
+fn xml(etichetta: &str) -> String {
+    etichetta
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,59 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use error::*;
+use problem::formats::*;
+use problem::graph::*;
+
+/// Graphs of the 9th DIMACS implementation challenge (`.gr`): `c` comments, the `p sp n m`
+/// problem line and an `a u v w` line for every arc; they are directed and weighted.
+pub struct DimacsReader;
+
+impl GraphReader for DimacsReader {
+    fn leggi(&self, sorgente: Sorgente, grafo: &mut GraphBuilder) -> Result<(), Error> {
+        let path = sorgente.path.clone();
+        grafo.dichiara(GraphType::Directed, true);
+        for line in sorgente.righe() {
+            let (numero, line) = line?;
+            let campi: Vec<&str> = line.split_whitespace().collect();
+            match campi.as_slice() {
+                [] | ["c", ..] | ["p", "sp", _, _] => {}
+                ["a", u, v, w] => match w.parse::<i32>() {
+                    Ok(costo) => grafo.arco(u, v, costo),
+                    Err(_) => return Err(Sorgente::malformata(&path, numero, &line)),
+                },
+                _ => return Err(Sorgente::malformata(&path, numero, &line)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Coordinates of the nodes of a DIMACS graph, read from the `v id x y` lines of a `.co`
+/// file; the states without coordinates get `None`
+pub fn leggi_coordinate(path: &str, grafo: &Graph) -> Result<Vec<Option<(f64, f64)>>, Error> {
+    let errore_file = |errore| Error::File {
+        path: path.to_string(),
+        errore,
+    };
+    let mut coordinate = vec![None; grafo.num_nodi()];
+    let file = File::open(path).map_err(errore_file)?;
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(errore_file)?;
+        let campi: Vec<&str> = line.split_whitespace().collect();
+        match campi.as_slice() {
+            [] | ["c", ..] | ["p", ..] => {}
+            ["v", id, x, y] => match (x.parse::<f64>(), y.parse::<f64>()) {
+                (Ok(x), Ok(y)) => {
+                    // the nodes without arcs are not part of the graph
+                    if let Ok(stato) = grafo.stato(id) {
+                        coordinate[stato as usize] = Some((x, y));
+                    }
+                }
+                _ => return Err(Sorgente::malformata(path, i + 1, &line)),
+            },
+            _ => return Err(Sorgente::malformata(path, i + 1, &line)),
+        }
+    }
+    Ok(coordinate)
+}
@@ -0,0 +1,66 @@
+use roxmltree::Document;
+
+use error::*;
+use problem::formats::*;
+use problem::graph::*;
+
+/// GraphML documents: the `edgedefault` of the graph gives its type, and the edges are
+/// weighted when an edge key is named "weight"; the labels are the ids of the nodes.
+pub struct GraphMlReader;
+
+impl GraphReader for GraphMlReader {
+    fn leggi(&self, sorgente: Sorgente, grafo: &mut GraphBuilder) -> Result<(), Error> {
+        let path = sorgente.path.clone();
+        let testo = sorgente.testo()?;
+        let non_valido = |messaggio: String| Error::InvalidDocument {
+            path: path.clone(),
+            messaggio,
+        };
+        let documento = Document::parse(&testo).map_err(|e| non_valido(e.to_string()))?;
+        let radice = documento.root_element();
+        let chiave_peso = radice
+            .children()
+            .filter(|n| n.has_tag_name("key"))
+            .find(|n| {
+                n.attribute("for").is_none_or(|f| f == "edge" || f == "all")
+                    && n.attribute("attr.name") == Some("weight")
+            })
+            .and_then(|n| n.attribute("id"));
+        let graph = radice
+            .children()
+            .find(|n| n.has_tag_name("graph"))
+            .ok_or_else(|| non_valido("manca l'elemento graph".to_string()))?;
+        let tipo = match graph.attribute("edgedefault") {
+            Some("undirected") => GraphType::Undirected,
+            _ => GraphType::Directed,
+        };
+        grafo.dichiara(tipo, chiave_peso.is_some());
+        for nodo in graph.children().filter(|n| n.has_tag_name("node")) {
+            match nodo.attribute("id") {
+                Some(id) => {
+                    grafo.nodo(id);
+                }
+                None => return Err(non_valido("nodo senza id".to_string())),
+            }
+        }
+        for arco in graph.children().filter(|n| n.has_tag_name("edge")) {
+            let (da, a) = match (arco.attribute("source"), arco.attribute("target")) {
+                (Some(da), Some(a)) => (da, a),
+                _ => return Err(non_valido("arco senza source o target".to_string())),
+            };
+            let peso = arco
+                .children()
+                .find(|d| d.has_tag_name("data") && d.attribute("key") == chiave_peso)
+                .and_then(|d| d.text());
+            let costo = match peso {
+                Some(peso) => match peso.trim().parse::<f64>() {
+                    Ok(peso) => peso.round() as i32,
+                    Err(_) => return Err(non_valido(format!("peso non valido: {}", peso))),
+                },
+                None => 0,
+            };
+            grafo.arco(da, a, costo);
+        }
+        Ok(())
+    }
+}
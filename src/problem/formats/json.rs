@@ -0,0 +1,99 @@
+use serde_json::Value;
+
+use error::*;
+use problem::formats::*;
+use problem::graph::*;
+
+/// JSON edge lists: an object with the `edges` (or `links`) array and optionally `directed`
+/// and `nodes`, or just the array of the edges. An edge is either `[da, a, peso]` or an object
+/// with `source`/`target` (or `from`/`to`) and `weight`; the ids can be strings or integers.
+/// Without `directed` the graph is directed, and it's weighted when some edge has a weight.
+pub struct JsonReader;
+
+impl GraphReader for JsonReader {
+    fn leggi(&self, sorgente: Sorgente, grafo: &mut GraphBuilder) -> Result<(), Error> {
+        let path = sorgente.path.clone();
+        let testo = sorgente.testo()?;
+        let non_valido = |messaggio: String| Error::InvalidDocument {
+            path: path.clone(),
+            messaggio,
+        };
+        let documento: Value =
+            serde_json::from_str(&testo).map_err(|e| non_valido(e.to_string()))?;
+        let (archi, diretto, nodi) = match &documento {
+            Value::Array(archi) => (archi, None, None),
+            Value::Object(grafo) => (
+                grafo
+                    .get("edges")
+                    .or_else(|| grafo.get("links"))
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| non_valido("manca l'array edges".to_string()))?,
+                grafo.get("directed").and_then(Value::as_bool),
+                grafo.get("nodes").and_then(Value::as_array),
+            ),
+            _ => return Err(non_valido("atteso un oggetto o un array".to_string())),
+        };
+        let mut letti = Vec::with_capacity(archi.len());
+        for arco in archi {
+            letti.push(
+                leggi_arco(arco).ok_or_else(|| non_valido(format!("arco non valido: {}", arco)))?,
+            );
+        }
+        let tipo = match diretto {
+            Some(false) => GraphType::Undirected,
+            _ => GraphType::Directed,
+        };
+        grafo.dichiara(tipo, letti.iter().any(|(_, _, peso)| peso.is_some()));
+        for nodo in nodi.into_iter().flatten() {
+            let id = match nodo {
+                Value::Object(nodo) => nodo.get("id").and_then(id),
+                _ => id(nodo),
+            };
+            match id {
+                Some(id) => {
+                    grafo.nodo(&id);
+                }
+                None => return Err(non_valido(format!("nodo non valido: {}", nodo))),
+            }
+        }
+        for (da, a, peso) in letti {
+            grafo.arco(&da, &a, peso.unwrap_or(0));
+        }
+        Ok(())
+    }
+}
+
+/// The label of a node id, integers are written without decimals
+fn id(valore: &Value) -> Option<String> {
+    match valore {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) if id.is_u64() || id.is_i64() => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn peso(valore: &Value) -> Option<i32> {
+    valore.as_f64().map(|peso| peso.round() as i32)
+}
+
+fn leggi_arco(arco: &Value) -> Option<(String, String, Option<i32>)> {
+    match arco {
+        Value::Array(campi) if campi.len() == 2 || campi.len() == 3 => {
+            let peso = match campi.get(2) {
+                Some(valore) => Some(peso(valore)?),
+                None => None,
+            };
+            Some((id(&campi[0])?, id(&campi[1])?, peso))
+        }
+        Value::Object(arco) => {
+            let da = arco.get("source").or_else(|| arco.get("from"))?;
+            let a = arco.get("target").or_else(|| arco.get("to"))?;
+            let peso = match arco.get("weight") {
+                Some(valore) => Some(peso(valore)?),
+                None => None,
+            };
+            Some((id(da)?, id(a)?, peso))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,144 @@
+pub mod dimacs;
+pub mod graphml;
+pub mod json;
+pub mod mtx;
+pub mod snap;
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use self::dimacs::*;
+use self::graphml::*;
+use self::json::*;
+use self::mtx::*;
+use self::snap::*;
+use error::*;
+use problem::compression::*;
+use problem::graph::*;
+
+/// Reader of a dataset format: declares the type of the graph to the builder, then adds the
+/// nodes and the edges of the dataset
+pub trait GraphReader {
+    fn leggi(&self, sorgente: Sorgente, grafo: &mut GraphBuilder) -> Result<(), Error>;
+}
+
+/// Formats of the text datasets
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum Formato {
+    /// Lista di archi SNAP, separati da spazi o virgole
+    Snap,
+    /// Matrix Market (.mtx)
+    Mtx,
+    /// DIMACS shortest path (.gr)
+    Dimacs,
+    /// GraphML (.graphml)
+    Graphml,
+    /// Lista di archi JSON (.json)
+    Json,
+}
+
+impl Formato {
+    /// The format named by the extension of `path`, ignoring the one of the compression;
+    /// any other extension is read as a SNAP edge list
+    pub fn da_estensione(path: &str) -> Formato {
+        let mut path = Path::new(path);
+        if Compressione::da_estensione(&path.to_string_lossy()).is_some() {
+            path = Path::new(path.file_stem().unwrap_or_default());
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("mtx") => Formato::Mtx,
+            Some("gr") => Formato::Dimacs,
+            Some("graphml") => Formato::Graphml,
+            Some("json") => Formato::Json,
+            _ => Formato::Snap,
+        }
+    }
+    pub fn lettore(&self) -> Box<dyn GraphReader> {
+        match self {
+            Formato::Snap => Box::new(SnapReader),
+            Formato::Mtx => Box::new(MatrixMarketReader),
+            Formato::Dimacs => Box::new(DimacsReader),
+            Formato::Graphml => Box::new(GraphMlReader),
+            Formato::Json => Box::new(JsonReader),
+        }
+    }
+}
+
+/// Decompressed content of a dataset, read by lines or as a whole document
+pub struct Sorgente {
+    pub path: String,
+    compressione: Compressione,
+    lettore: Box<dyn BufRead + Send>,
+}
+
+impl Sorgente {
+    pub fn apri(path: &str) -> Result<Self, Error> {
+        let (lettore, compressione) = apri(path)?;
+        Ok(Sorgente {
+            path: path.to_string(),
+            compressione,
+            lettore,
+        })
+    }
+    /// Lines numbered from 1, without the line terminator
+    pub fn righe(self) -> impl Iterator<Item = Result<(usize, String), Error>> {
+        let (path, compressione) = (self.path, self.compressione);
+        self.lettore
+            .split(b'\n')
+            .enumerate()
+            .map(move |(i, line)| decodifica_riga(&path, compressione, i + 1, line))
+    }
+    /// The whole content, for the formats that are not read by lines
+    pub fn testo(mut self) -> Result<String, Error> {
+        let mut byte = Vec::new();
+        self.lettore
+            .read_to_end(&mut byte)
+            .map_err(|errore| errore_lettura(&self.path, self.compressione, errore))?;
+        String::from_utf8(byte).map_err(|_| Error::InvalidDocument {
+            path: self.path,
+            messaggio: "il contenuto non è UTF-8".to_string(),
+        })
+    }
+    /// Error for the line `riga` that can't be parsed
+    pub fn malformata(path: &str, riga: usize, contenuto: &str) -> Error {
+        Error::MalformedLine {
+            path: path.to_string(),
+            riga,
+            contenuto: contenuto.to_string(),
+        }
+    }
+}
+
+fn errore_lettura(path: &str, compressione: Compressione, errore: io::Error) -> Error {
+    let path = path.to_string();
+    match compressione {
+        Compressione::Nessuna => Error::File { path, errore },
+        _ => Error::Decompress {
+            path,
+            compressione,
+            errore,
+        },
+    }
+}
+
+/// Decodes the line `numero` (from 1) of the dataset, dropping the `\r` of CRLF files
+fn decodifica_riga(
+    dataset_path: &str,
+    compressione: Compressione,
+    numero: usize,
+    line: io::Result<Vec<u8>>,
+) -> Result<(usize, String), Error> {
+    let mut line = line.map_err(|errore| errore_lettura(dataset_path, compressione, errore))?;
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+    match String::from_utf8(line) {
+        Ok(line) => Ok((numero, line)),
+        Err(e) => Err(Error::MalformedLine {
+            path: dataset_path.to_string(),
+            riga: numero,
+            contenuto: String::from_utf8_lossy(e.as_bytes()).into_owned(),
+        }),
+    }
+}
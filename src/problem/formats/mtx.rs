@@ -0,0 +1,74 @@
+use error::*;
+use problem::formats::*;
+use problem::graph::*;
+
+/// Matrix Market coordinate matrices: the entry `i j [valore]` is the edge from `i` to `j`.
+/// The symmetric matrices are undirected graphs, the skew-symmetric ones are directed with the
+/// entry mirrored by `-valore`, the `pattern` ones are unweighted and the real values are
+/// rounded to the nearest integer cost; complex and hermitian matrices have no cost to give
+/// and are refused. The labels are the 1-based indices.
+pub struct MatrixMarketReader;
+
+impl GraphReader for MatrixMarketReader {
+    fn leggi(&self, sorgente: Sorgente, grafo: &mut GraphBuilder) -> Result<(), Error> {
+        let path = sorgente.path.clone();
+        let mut lines = sorgente.righe();
+        let (numero, intestazione) = match lines.next() {
+            Some(line) => line?,
+            None => return Err(Sorgente::malformata(&path, 1, "")),
+        };
+        let campi: Vec<String> = intestazione
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        if campi.len() < 5
+            || campi[0] != "%%matrixmarket"
+            || campi[2] != "coordinate"
+            || campi[3] == "complex"
+        {
+            return Err(Sorgente::malformata(&path, numero, &intestazione));
+        }
+        let pesato = campi[3] != "pattern";
+        let antisimmetrica = campi[4] == "skew-symmetric";
+        let tipo = match campi[4].as_str() {
+            "general" | "skew-symmetric" => GraphType::Directed,
+            "symmetric" => GraphType::Undirected,
+            _ => return Err(Sorgente::malformata(&path, numero, &intestazione)),
+        };
+        grafo.dichiara(tipo, pesato);
+        // the first line that is not a comment holds the sizes of the matrix
+        let mut dimensioni = false;
+        for line in lines {
+            let (numero, line) = line?;
+            if line.trim().is_empty() || line.starts_with('%') {
+                continue;
+            }
+            let campi: Vec<&str> = line.split_whitespace().collect();
+            if !dimensioni {
+                if campi.len() != 3 || campi.iter().any(|c| c.parse::<usize>().is_err()) {
+                    return Err(Sorgente::malformata(&path, numero, &line));
+                }
+                dimensioni = true;
+                continue;
+            }
+            let costo = match campi.get(2) {
+                Some(valore) if pesato => match valore.parse::<f64>() {
+                    Ok(valore) => valore.round() as i32,
+                    Err(_) => return Err(Sorgente::malformata(&path, numero, &line)),
+                },
+                _ => 0,
+            };
+            match (campi.first(), campi.get(1)) {
+                (Some(i), Some(j)) if i.parse::<usize>().is_ok() && j.parse::<usize>().is_ok() => {
+                    grafo.arco(i, j, costo);
+                    // only the lower triangle is stored, the diagonal of these is all zero
+                    if antisimmetrica && i != j {
+                        grafo.arco(j, i, costo.saturating_neg());
+                    }
+                }
+                _ => return Err(Sorgente::malformata(&path, numero, &line)),
+            }
+        }
+        Ok(())
+    }
+}
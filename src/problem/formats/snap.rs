@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use error::*;
+use problem::formats::*;
+use problem::graph::*;
+
+/// Lines sent at once to a worker of the parallel loader
+const RIGHE_PER_BLOCCO: usize = 16 * 1024;
+
+/// Edge lists of the SNAP datasets: a line per edge, the columns and the separator are the
+/// ones of `LoadOptions`; the lines starting with `#` are comments.
+/// The type is guessed from the "Directed"/"Undirected" of the first comment and the weights
+/// from its "weighted"/"unweighted", as written by the export; the datasets without a header
+/// are undirected and weighted, the ones with a header but no weight word are unweighted.
+/// What `LoadOptions` gives explicitly is never guessed, whatever the header says.
+/// With `LoadOptions::thread` greater than 1 the lines are split in blocks, parsed by a pool of
+/// workers and added to the graph in the order of the file, so the graph is the same.
+pub struct SnapReader;
+
+impl GraphReader for SnapReader {
+    fn leggi(&self, sorgente: Sorgente, grafo: &mut GraphBuilder) -> Result<(), Error> {
+        let path = sorgente.path.clone();
+        let opzioni = grafo.opzioni().clone();
+        let mut lines = sorgente.righe().peekable();
+        // the first line is only looked at for what the options don't give, so the header
+        // never disagrees with them
+        let (intestazione, pesi) = match lines.peek() {
+            Some(Ok((_, line))) if opzioni.tipo.is_none() || opzioni.pesato.is_none() => {
+                intestazione(line)
+            }
+            _ => (None, None),
+        };
+        let tipo = opzioni
+            .tipo
+            .or(intestazione)
+            .unwrap_or(GraphType::Undirected);
+        let pesato = opzioni.pesato.or(pesi).unwrap_or(intestazione.is_none());
+        grafo.dichiara(tipo, pesato);
+        let campi = Campi {
+            path: &path,
+            opzioni: &opzioni,
+            pesato: grafo.is_weighted(),
+            // a guessed weight may be missing, an explicit one is required
+            peso_obbligatorio: opzioni.pesato == Some(true),
+        };
+        if opzioni.thread > 1 {
+            return in_parallelo(lines, &campi, opzioni.thread, grafo);
+        }
+
+        // Load every line into the adjacency lists
+        for line in lines {
+            let (numero, line) = line?;
+            if let Some((from, to, costo)) = campi.arco(numero, &line)? {
+                grafo.arco(from, to, costo);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How the fields of a line are split and parsed
+struct Campi<'a> {
+    path: &'a str,
+    opzioni: &'a LoadOptions,
+    pesato: bool,
+    peso_obbligatorio: bool,
+}
+
+impl<'a> Campi<'a> {
+    /// The edge of the line `numero`, `None` for the comments and the empty lines
+    fn arco<'l>(
+        &self,
+        numero: usize,
+        line: &'l str,
+    ) -> Result<Option<(&'l str, &'l str, i32)>, Error> {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            return Ok(None);
+        }
+        let malformata = || Sorgente::malformata(self.path, numero, line);
+        let campi: Vec<&str> = match self.opzioni.separatore {
+            Some(separatore) => line.split(separatore).map(str::trim).collect(),
+            None => line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|c| !c.is_empty())
+                .collect(),
+        };
+        let (from, to) = match (
+            campi.get(self.opzioni.colonna_da),
+            campi.get(self.opzioni.colonna_a),
+        ) {
+            (Some(from), Some(to)) if !from.is_empty() && !to.is_empty() => (*from, *to),
+            _ => return Err(malformata()),
+        };
+        let mut costo = 0;
+        // if the graph is weighted, the weight column is the cost of the edge
+        if self.pesato {
+            match campi.get(self.opzioni.colonna_peso) {
+                Some(c) => costo = c.parse::<i32>().map_err(|_| malformata())?,
+                None if self.peso_obbligatorio => return Err(malformata()),
+                None => {}
+            }
+        }
+        Ok(Some((from, to, costo)))
+    }
+}
+
+type Blocco = (usize, Vec<(usize, String)>);
+type Archi = (usize, Result<Vec<(String, String, i32)>, Error>);
+
+/// Decompresses in a thread and parses blocks of lines in `thread` workers; the blocks are
+/// added to `grafo` by the calling thread in the order they were read, so that the states are
+/// numbered and the first error is reported as by the sequential loader
+fn in_parallelo<I>(
+    lines: I,
+    campi: &Campi,
+    thread: usize,
+    grafo: &mut GraphBuilder,
+) -> Result<(), Error>
+where
+    I: Iterator<Item = Result<(usize, String), Error>> + Send,
+{
+    thread::scope(|scope| {
+        let (invia_blocchi, blocchi) = mpsc::sync_channel(thread * 2);
+        let blocchi = Arc::new(Mutex::new(blocchi));
+        let (invia_archi, archi) = mpsc::sync_channel(thread * 2);
+        {
+            let invia_archi = invia_archi.clone();
+            scope.spawn(move || leggi_blocchi(lines, &invia_blocchi, &invia_archi));
+        }
+        for _ in 0..thread {
+            let blocchi = Arc::clone(&blocchi);
+            let invia_archi = invia_archi.clone();
+            scope.spawn(move || analizza_blocchi(campi, &blocchi, &invia_archi));
+        }
+        // the channel is closed once every thread is done
+        drop(invia_archi);
+
+        let mut in_attesa = BTreeMap::new();
+        let mut prossimo = 0;
+        for (indice, archi) in archi {
+            in_attesa.insert(indice, archi);
+            while let Some(archi) = in_attesa.remove(&prossimo) {
+                for (from, to, costo) in archi? {
+                    grafo.arco(&from, &to, costo);
+                }
+                prossimo += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Type and weights declared by the header of a SNAP dataset, as "# Directed graph" or
+/// "# Undirected weighted graph"
+fn intestazione(line: &str) -> (Option<GraphType>, Option<bool>) {
+    let minuscola = line.to_lowercase();
+    let pesi = if minuscola.contains("unweighted") {
+        Some(false)
+    } else if minuscola.contains("weighted") {
+        Some(true)
+    } else {
+        None
+    };
+    if line.contains("Undirected") {
+        (Some(GraphType::Undirected), pesi)
+    } else if line.contains("Directed") {
+        (Some(GraphType::Directed), pesi)
+    } else {
+        (None, pesi)
+    }
+}
+
+/// Splits the lines in numbered blocks; a read error takes the place of the next block
+fn leggi_blocchi<I>(lines: I, blocchi: &SyncSender<Blocco>, archi: &SyncSender<Archi>)
+where
+    I: Iterator<Item = Result<(usize, String), Error>>,
+{
+    let mut indice = 0;
+    let mut blocco = Vec::with_capacity(RIGHE_PER_BLOCCO);
+    for line in lines {
+        match line {
+            Ok(line) => blocco.push(line),
+            Err(errore) => {
+                if !blocco.is_empty() {
+                    if blocchi.send((indice, blocco)).is_err() {
+                        return;
+                    }
+                    indice += 1;
+                }
+                let _ = archi.send((indice, Err(errore)));
+                return;
+            }
+        }
+        if blocco.len() == RIGHE_PER_BLOCCO {
+            let pieno = std::mem::replace(&mut blocco, Vec::with_capacity(RIGHE_PER_BLOCCO));
+            // the loading stopped on an error
+            if blocchi.send((indice, pieno)).is_err() {
+                return;
+            }
+            indice += 1;
+        }
+    }
+    if !blocco.is_empty() {
+        let _ = blocchi.send((indice, blocco));
+    }
+}
+
+/// Parses the blocks until there are no more, or the loading stopped on an error
+fn analizza_blocchi(campi: &Campi, blocchi: &Mutex<Receiver<Blocco>>, archi: &SyncSender<Archi>) {
+    loop {
+        // the lock is released as soon as a block is received
+        let ricevuto = blocchi.lock().unwrap().recv();
+        let (indice, blocco) = match ricevuto {
+            Ok(blocco) => blocco,
+            Err(_) => return,
+        };
+        let analizzati = blocco
+            .iter()
+            .filter_map(|(numero, line)| campi.arco(*numero, line).transpose())
+            .map(|arco| arco.map(|(from, to, costo)| (from.to_string(), to.to_string(), costo)))
+            .collect();
+        if archi.send((indice, analizzati)).is_err() {
+            return;
+        }
+    }
+}
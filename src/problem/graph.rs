@@ -1,157 +1,437 @@
-use flate2::read::GzDecoder;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::fmt;
+use std::time::Duration;
 
+use clap::ValueEnum;
+
+use error::*;
+use problem::binary::{self, Array};
+use problem::formats::*;
+use problem::idmap::*;
 use problem::node::*;
 use timed_run;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GraphType {
+    Directed,
+    Undirected,
+}
+
+impl fmt::Display for GraphType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphType::Directed => write!(f, "Directed"),
+            GraphType::Undirected => write!(f, "Undirected"),
+        }
+    }
+}
+
+/// What is kept of the edges of a weighted graph given more than once; in unweighted graphs
+/// the duplicates are always dropped
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Duplicati {
+    /// The cost of the first occurrence in the dataset
+    KeepFirst,
+    /// The lowest cost
+    KeepMin,
+    /// The sum of the costs
+    Sum,
+    /// The loading fails
+    Error,
+}
+
+/// How a text dataset is read: the fields left to `None` are guessed from the dataset,
+/// the columns and the separator only apply to the SNAP edge lists and are numbered from 0.
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Without it the type is read from the "Directed"/"Undirected" of the first comment,
+    /// a dataset without that comment is undirected
+    pub tipo: Option<GraphType>,
+    /// Without it only the datasets without the header comment are weighted
+    pub pesato: Option<bool>,
+    pub colonna_da: usize,
+    pub colonna_a: usize,
+    pub colonna_peso: usize,
+    /// Without it the fields are separated by commas or whitespace
+    pub separatore: Option<char>,
+    /// Without it the format is chosen by the extension of the file
+    pub formato: Option<Formato>,
+    pub duplicati: Duplicati,
+    /// Workers parsing the SNAP edge lists, with 1 they are read by the calling thread
+    pub thread: usize,
+    /// Prints the type, the size and the loading time of the graph, false by default
+    pub riepilogo: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            tipo: None,
+            pesato: None,
+            colonna_da: 0,
+            colonna_a: 1,
+            colonna_peso: 2,
+            separatore: None,
+            formato: None,
+            duplicati: Duplicati::KeepFirst,
+            thread: 1,
+            riepilogo: false,
+        }
+    }
+}
+
+/// Graph stored in CSR (compressed sparse row) form: the actions of the state `s`
+/// are `targets[offsets[s]..offsets[s + 1]]`, with the matching costs in `costi`.
+/// Directed graphs also keep the reverse adjacency in the same form.
+/// The states are the dense indices assigned by `etichette` to the nodes of the dataset.
 pub struct Graph {
-    gtype: String,
-    nodi: Vec<Node>,
-    edge_count: u32,
+    gtype: GraphType,
+    pesato: bool,
+    etichette: IdMap,
+    offsets: Array<usize>,
+    targets: Array<State>,
+    costi: Array<i32>,
+    inv_offsets: Vec<usize>,
+    inv_targets: Vec<State>,
+    inv_costi: Vec<i32>,
+    edge_count: usize,
+}
+
+impl Default for Graph {
+    fn default() -> Self {
+        Graph::new()
+    }
 }
 
 impl Graph {
     pub fn new() -> Graph {
         Graph {
-            gtype: "None".to_string(),
-            nodi: Vec::new(),
+            gtype: GraphType::Undirected,
+            pesato: false,
+            etichette: IdMap::new(),
+            offsets: vec![0].into(),
+            targets: Vec::new().into(),
+            costi: Vec::new().into(),
+            inv_offsets: vec![0],
+            inv_targets: Vec::new(),
+            inv_costi: Vec::new(),
             edge_count: 0,
         }
     }
-    pub fn from_file(dataset_path: &str) -> Graph {
+    pub fn from_file(dataset_path: &str) -> Result<Graph, Error> {
+        Graph::from_file_with_options(dataset_path, &LoadOptions::default())
+    }
+    /// The files written by `eia convert` are recognized and loaded without parsing,
+    /// `opzioni` only applies to the text datasets, except for `riepilogo`
+    pub fn from_file_with_options(
+        dataset_path: &str,
+        opzioni: &LoadOptions,
+    ) -> Result<Graph, Error> {
+        if binary::is_binary(dataset_path) {
+            let graph;
+            let elapsed = timed_run!({
+                graph = binary::leggi(dataset_path)?;
+            });
+            if opzioni.riepilogo {
+                println!("Tipo di Grafo: {} (binario)", graph.descrizione());
+                graph.riepilogo(elapsed);
+            }
+            return Ok(graph);
+        }
         let mut graph = Graph::new();
-        graph.load_dataset(dataset_path);
+        graph.load_dataset(dataset_path, opzioni)?;
+        Ok(graph)
+    }
+    /// Graph made of the given forward CSR arrays, the reverse ones are rebuilt
+    pub(crate) fn from_csr(
+        gtype: GraphType,
+        pesato: bool,
+        etichette: IdMap,
+        offsets: Array<usize>,
+        targets: Array<State>,
+        costi: Array<i32>,
+        archi: usize,
+    ) -> Graph {
+        let mut graph = Graph {
+            gtype,
+            pesato,
+            etichette,
+            offsets,
+            targets,
+            costi,
+            edge_count: archi,
+            ..Graph::new()
+        };
+        if graph.is_directed() {
+            graph.inverti();
+        }
         graph
     }
-    pub fn nodi(&self) -> Vec<Node> {
-        self.nodi.clone()
+    pub fn num_nodi(&self) -> usize {
+        self.offsets.len() - 1
+    }
+    pub fn num_archi(&self) -> usize {
+        self.edge_count
+    }
+    pub fn has_negative_costs(&self) -> bool {
+        self.costi.iter().any(|&c| c < 0)
+    }
+    pub fn gtype(&self) -> GraphType {
+        self.gtype
+    }
+    pub fn is_directed(&self) -> bool {
+        self.gtype == GraphType::Directed
+    }
+    pub fn is_weighted(&self) -> bool {
+        self.pesato
+    }
+    /// Fails when `stato` is not a state of the graph
+    pub fn controlla(&self, stato: State) -> Result<(), Error> {
+        if stato as usize >= self.num_nodi() {
+            return Err(Error::StateOutOfRange {
+                stato,
+                num_stati: self.num_nodi(),
+            });
+        }
+        Ok(())
+    }
+    /// The state of the node with the given label in the dataset
+    pub fn stato(&self, etichetta: &str) -> Result<State, Error> {
+        self.etichette
+            .stato(etichetta)
+            .ok_or_else(|| Error::UnknownLabel(etichetta.to_string()))
+    }
+    /// The label in the dataset of the node of `stato`
+    pub fn etichetta(&self, stato: State) -> String {
+        self.etichette.etichetta(stato)
+    }
+    pub fn etichette(&self) -> &IdMap {
+        &self.etichette
+    }
+    /// True when no action starts from or reaches `stato`, as for the ids missing from a dataset
+    pub fn is_isolated(&self, stato: State) -> bool {
+        self.vicini(stato).is_empty() && self.azioni_inverse(stato).next().is_none()
+    }
+    /// States reachable from `stato` with a single action
+    pub fn vicini(&self, stato: State) -> &[State] {
+        let s = stato as usize;
+        &self.targets[self.offsets[s]..self.offsets[s + 1]]
+    }
+    /// Costs of the actions of `stato`, in the same order as `vicini`
+    pub fn costi(&self, stato: State) -> &[i32] {
+        let s = stato as usize;
+        &self.costi[self.offsets[s]..self.offsets[s + 1]]
+    }
+    pub fn azioni(&self, stato: State) -> impl Iterator<Item = Action> + '_ {
+        self.vicini(stato)
+            .iter()
+            .zip(self.costi(stato))
+            .map(|(&risultato, &costo)| Action { risultato, costo })
     }
-    pub fn load_dataset(&mut self, dataset_path: &str) {
+    /// Actions reaching `stato`: `risultato` is the source of the edge, `costo` its cost
+    pub fn azioni_inverse(&self, stato: State) -> impl Iterator<Item = Action> + '_ {
+        // undirected graphs are their own reverse
+        let (offsets, targets, costi) = if self.is_directed() {
+            (
+                &self.inv_offsets[..],
+                &self.inv_targets[..],
+                &self.inv_costi[..],
+            )
+        } else {
+            (&self.offsets[..], &self.targets[..], &self.costi[..])
+        };
+        let s = stato as usize;
+        targets[offsets[s]..offsets[s + 1]]
+            .iter()
+            .zip(&costi[offsets[s]..offsets[s + 1]])
+            .map(|(&risultato, &costo)| Action { risultato, costo })
+    }
+    /// Reads a text dataset in the format of `opzioni`, or the one of its extension
+    pub fn load_dataset(&mut self, dataset_path: &str, opzioni: &LoadOptions) -> Result<(), Error> {
         let elapsed = timed_run!({
-            // read the dataset file
-            let mut lines = BufReader::new(GzDecoder::new(File::open(dataset_path).unwrap()))
-                .lines()
-                .map(|line| line.unwrap()).peekable();
-            loop {
-                // get the first line
-                if let Some(line) = lines.peek() {
-                    // guess the graph type
-                    if line.contains("Directed") {
-                        self.gtype = "Directed".to_string();
-                    } else if line.contains("Undirected") {
-                        self.gtype = "Undirected".to_string();
-                    } else {
-                        self.gtype = "Labeled".to_string();
-                    break;
-                    }
-                    // if the current line is a comment, go to the next one
-                    if line.contains("#") {
-                        // if the next line is not a comment, break the loop
-                        if !lines.next().unwrap().contains("#") {
-                            break;
-                        }
-                        break;
-                    }
-                }
+            let sorgente = Sorgente::apri(dataset_path)?;
+            let formato = opzioni
+                .formato
+                .unwrap_or_else(|| Formato::da_estensione(dataset_path));
+            let mut costruttore = GraphBuilder::new(opzioni);
+            formato.lettore().leggi(sorgente, &mut costruttore)?;
+            *self = costruttore.costruisci()?;
+        });
+        if opzioni.riepilogo {
+            println!("Tipo di Grafo: {}", self.descrizione());
+            self.riepilogo(elapsed);
+        }
+        Ok(())
+    }
+    fn descrizione(&self) -> String {
+        if self.pesato {
+            format!("{}, pesato", self.gtype)
+        } else {
+            self.gtype.to_string()
+        }
+    }
+    fn riepilogo(&self, elapsed: Duration) {
+        println!("Durata caricamento: {:.3}s", elapsed.as_secs_f64());
+        println!("Caricati {} nodi", self.num_nodi());
+        println!("Caricati {} archi", self.edge_count);
+    }
+    /// Replaces the CSR arrays with the content of the given adjacency lists
+    fn compatta(&mut self, azioni: Vec<Vec<Action>>) {
+        let totale = azioni.iter().map(Vec::len).sum();
+        let mut offsets = Vec::with_capacity(azioni.len() + 1);
+        let mut targets = Vec::with_capacity(totale);
+        let mut costi = Vec::with_capacity(totale);
+        offsets.push(0);
+        for lista in azioni {
+            for azione in lista {
+                targets.push(azione.risultato);
+                costi.push(azione.costo);
             }
-            println!("Tipo di Grafo: {}", self.gtype);
-            // print the first line!
-
-            // Load every line into the BTree
-            for line in lines {
-                let line = line.replace(",", " ");
-                let mut iter = line.split_whitespace();
-                if let (Some(from), Some(to)) = (iter.next(), iter.next()) {
-                    if let (Ok(from), Ok(to)) = (from.parse::<State>(), to.parse::<State>()) {
-                        let mut costo = 0;
-                        // if the graph is labeled, it is also undirected
-                        let is_labeled = self.gtype == "Labeled";
-                        let is_undirected = self.gtype == "Undirected" || is_labeled;
-                        let from_node_exists = self.nodi.len() > from as usize;
-                        let to_node_exists = self.nodi.len() > to as usize;
-                        let from_to_action_exists = if from_node_exists {
-                            self.nodi[from as usize]
-                                .azioni
-                                .iter()
-                                .find(|&a| a.risultato == to && a.costo == 0)
-                                .is_some()
-                        } else {
-                            false
-                        };
-                        let to_from_action_exists = if to_node_exists {
-                            self.nodi[to as usize]
-                                .azioni
-                                .iter()
-                                .find(|&a| a.risultato == from && a.costo == 0)
-                                .is_some()
-                        } else {
-                            false
-                        };
+            offsets.push(targets.len());
+        }
+        self.offsets = offsets.into();
+        self.targets = targets.into();
+        self.costi = costi.into();
+    }
+    /// Builds the reverse CSR arrays from the forward ones, with a counting sort on the targets
+    fn inverti(&mut self) {
+        let n = self.num_nodi();
+        let mut gradi = vec![0; n + 1];
+        for &t in self.targets.iter() {
+            gradi[t as usize + 1] += 1;
+        }
+        for i in 0..n {
+            gradi[i + 1] += gradi[i];
+        }
+        self.inv_offsets = gradi.clone();
+        // gradi becomes the next free position of every reverse list
+        self.inv_targets = vec![0; self.targets.len()];
+        self.inv_costi = vec![0; self.costi.len()];
+        for from in 0..n {
+            for i in self.offsets[from]..self.offsets[from + 1] {
+                let to = self.targets[i] as usize;
+                self.inv_targets[gradi[to]] = from as State;
+                self.inv_costi[gradi[to]] = self.costi[i];
+                gradi[to] += 1;
+            }
+        }
+    }
+}
 
-                        // if the graph is labeled, the next number is the cost of the edge
-                        if is_labeled {
-                            if let Some(c) = iter.next() {
-                                if let Ok(c) = c.parse::<i32>() {
-                                    costo = c;
-                                }
-                            }
-                        }
+/// Adjacency lists filled by a `GraphReader` and compacted into a `Graph` at the end
+pub struct GraphBuilder<'a> {
+    opzioni: &'a LoadOptions,
+    gtype: GraphType,
+    pesato: bool,
+    etichette: IdMap,
+    azioni: Vec<Vec<Action>>,
+}
 
-                        // if the node "from" doesn't exist, create it
-                        if !from_node_exists {
-                            self.nodi.resize(
-                                from as usize + 1,
-                                Node {
-                                    stato: from,
-                                    azioni: Vec::new(),
-                                    genitore: None,
-                                    costo_cammino: 0,
-                                    profondita: 0,
-                                },
-                            );
-                        }
-                        // if the action from -> to doesn't exist, create it and increment the edge count
-                        if !from_to_action_exists {
-                            self.nodi[from as usize].azioni.push(Action {
-                                risultato: to,
-                                costo,
-                            });
-                            if is_undirected {
-                                self.edge_count += 1;
-                            } else {
-                                if to_from_action_exists {
-                                    self.edge_count += 1;
-                                }
-                            }
-                        }
-                        // same as above, but for the "to" node, only if the graph is undirected
-                        if is_undirected {
-                            if !to_node_exists {
-                                self.nodi.resize(
-                                    to as usize + 1,
-                                    Node {
-                                        stato: to,
-                                        azioni: Vec::new(),
-                                        genitore: None,
-                                        costo_cammino: 0,
-                                        profondita: 0,
-                                    },
-                                );
-                            }
-                            if !to_from_action_exists {
-                                self.nodi[to as usize].azioni.push(Action {
-                                    risultato: from,
-                                    costo,
-                                });
-                            }
-                        }
-                    }
+impl<'a> GraphBuilder<'a> {
+    pub fn new(opzioni: &'a LoadOptions) -> Self {
+        GraphBuilder {
+            opzioni,
+            gtype: opzioni.tipo.unwrap_or(GraphType::Undirected),
+            pesato: opzioni.pesato.unwrap_or(false),
+            etichette: IdMap::new(),
+            azioni: Vec::new(),
+        }
+    }
+    pub fn opzioni(&self) -> &LoadOptions {
+        self.opzioni
+    }
+    /// Type and weights declared by the dataset, the options take precedence;
+    /// it must be called before adding the edges
+    pub fn dichiara(&mut self, tipo: GraphType, pesato: bool) {
+        self.gtype = self.opzioni.tipo.unwrap_or(tipo);
+        self.pesato = self.opzioni.pesato.unwrap_or(pesato);
+    }
+    pub fn is_weighted(&self) -> bool {
+        self.pesato
+    }
+    /// The state of the node `etichetta`, added without actions if it's new
+    pub fn nodo(&mut self, etichetta: &str) -> State {
+        let stato = self.etichette.inserisci(etichetta);
+        // the node must exist even when it has no actions
+        if self.azioni.len() <= stato as usize {
+            self.azioni.resize(stato as usize + 1, Vec::new());
+        }
+        stato
+    }
+    /// Adds the edge between the nodes `da` and `a`; `costo` is ignored by unweighted graphs.
+    /// The duplicates are only merged by `costruisci`
+    pub fn arco(&mut self, da: &str, a: &str, costo: i32) {
+        let from = self.nodo(da);
+        let to = self.nodo(a);
+        let costo = if self.pesato { costo } else { 0 };
+        self.azioni[from as usize].push(Action {
+            risultato: to,
+            costo,
+        });
+        // an undirected self-loop is a single action
+        if self.gtype == GraphType::Undirected && from != to {
+            self.azioni[to as usize].push(Action {
+                risultato: from,
+                costo,
+            });
+        }
+    }
+    /// Sorts the actions of every state by their result, merging the duplicate edges as
+    /// asked by `LoadOptions::duplicati`, and compacts them into a `Graph`
+    pub fn costruisci(mut self) -> Result<Graph, Error> {
+        let duplicati = if self.pesato {
+            self.opzioni.duplicati
+        } else {
+            Duplicati::KeepFirst
+        };
+        let etichette = &self.etichette;
+        let mut cappi = 0;
+        for (s, lista) in self.azioni.iter_mut().enumerate() {
+            // the sort is stable, so the first occurrence comes first
+            lista.sort_by_key(|a| a.risultato);
+            let mut duplicato = None;
+            lista.dedup_by(|successiva, prima| {
+                if successiva.risultato != prima.risultato {
+                    return false;
+                }
+                match duplicati {
+                    Duplicati::KeepFirst => {}
+                    Duplicati::KeepMin => prima.costo = prima.costo.min(successiva.costo),
+                    Duplicati::Sum => prima.costo = prima.costo.saturating_add(successiva.costo),
+                    Duplicati::Error => duplicato = Some(prima.risultato),
                 }
+                true
+            });
+            if let Some(t) = duplicato {
+                return Err(Error::DuplicateEdge {
+                    da: etichette.etichetta(s as State),
+                    a: etichette.etichetta(t),
+                });
             }
-        });
-        println!("Durata caricamento: {:.3}s", elapsed.as_secs_f64());
-        println!("Caricati {} nodi", self.nodi.len());
-        println!("Caricati {} archi", self.edge_count);
+            if lista
+                .binary_search_by_key(&(s as State), |a| a.risultato)
+                .is_ok()
+            {
+                cappi += 1;
+            }
+        }
+        let azioni: usize = self.azioni.iter().map(Vec::len).sum();
+        // an undirected edge is stored as two actions, unless it's a self-loop
+        let archi = if self.gtype == GraphType::Directed {
+            azioni
+        } else {
+            (azioni + cappi) / 2
+        };
+        let mut graph = Graph {
+            gtype: self.gtype,
+            pesato: self.pesato,
+            etichette: self.etichette,
+            edge_count: archi,
+            ..Graph::new()
+        };
+        graph.compatta(self.azioni);
+        if graph.is_directed() {
+            graph.inverti();
+        }
+        Ok(graph)
     }
 }
@@ -0,0 +1,206 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use clap::ValueEnum;
+use rand::seq::SliceRandom;
+
+use error::*;
+use problem::graph::*;
+use problem::node::*;
+
+/// Estimate of the cost of the cheapest path from a state to the goal
+pub trait Heuristic<S = State> {
+    fn h(&self, state: &S) -> i32;
+}
+
+/// Heuristics selectable from the command line
+#[derive(Debug, Clone, ValueEnum, PartialEq)]
+pub enum Euristica {
+    Zero,
+    Landmark,
+    File,
+    /// Distanza di Manhattan (puzzle e labirinto)
+    Manhattan,
+    /// Tessere fuori posto (puzzle)
+    Misplaced,
+    /// Distanza in linea d'aria da Bucharest (Romania)
+    StraightLine,
+    /// Distanza euclidea tra le coordinate dei nodi (--coordinates)
+    Euclidean,
+}
+
+/// Always 0: A* behaves like uniform-cost search
+pub struct ZeroHeuristic;
+
+impl<S> Heuristic<S> for ZeroHeuristic {
+    fn h(&self, _state: &S) -> i32 {
+        0
+    }
+}
+
+/// ALT heuristic: lower bounds on the distance to the goal obtained through the triangle
+/// inequality on the distances from and to a few landmark states.
+pub struct LandmarkHeuristic {
+    /// distances from every landmark to every state
+    da: Vec<Vec<i32>>,
+    /// distances from every state to every landmark
+    verso: Vec<Vec<i32>>,
+    stato_finale: State,
+}
+
+impl LandmarkHeuristic {
+    pub fn new(grafo: &Graph, landmarks: &[State], stato_finale: State) -> Result<Self, Error> {
+        for &l in landmarks {
+            grafo.controlla(l)?;
+        }
+        Ok(LandmarkHeuristic::calcola(grafo, landmarks, stato_finale))
+    }
+    /// Picks `quanti` distinct landmarks at random among the states of the graph
+    pub fn casuali(grafo: &Graph, quanti: usize, stato_finale: State) -> Self {
+        let stati: Vec<State> = (0..grafo.num_nodi() as State).collect();
+        let landmarks: Vec<State> = stati
+            .choose_multiple(&mut rand::thread_rng(), quanti)
+            .cloned()
+            .collect();
+        LandmarkHeuristic::calcola(grafo, &landmarks, stato_finale)
+    }
+    /// Without non-negative costs the distances are not defined, no landmark is used then
+    fn calcola(grafo: &Graph, landmarks: &[State], stato_finale: State) -> Self {
+        let landmarks = if grafo.has_negative_costs() {
+            &[]
+        } else {
+            landmarks
+        };
+        LandmarkHeuristic {
+            da: landmarks
+                .iter()
+                .map(|&l| distanze(grafo, l, true))
+                .collect(),
+            verso: landmarks
+                .iter()
+                .map(|&l| distanze(grafo, l, false))
+                .collect(),
+            stato_finale,
+        }
+    }
+}
+
+impl Heuristic for LandmarkHeuristic {
+    fn h(&self, state: &State) -> i32 {
+        let (s, t) = (*state as usize, self.stato_finale as usize);
+        let mut h = 0;
+        // d(L, t) - d(L, s) <= d(s, t) and d(s, L) - d(t, L) <= d(s, t)
+        for d in &self.da {
+            if d[s] != i32::MAX && d[t] != i32::MAX {
+                h = h.max(d[t] - d[s]);
+            }
+        }
+        for d in &self.verso {
+            if d[s] != i32::MAX && d[t] != i32::MAX {
+                h = h.max(d[s] - d[t]);
+            }
+        }
+        h
+    }
+}
+
+/// Values read from a text file with a `label value` pair per line, where the labels are the
+/// ones of the nodes in the dataset; missing states get 0
+pub struct FileHeuristic {
+    valori: HashMap<State, i32>,
+}
+
+impl FileHeuristic {
+    pub fn from_file(path: &str, grafo: &Graph) -> Result<Self, Error> {
+        let errore_file = |errore| Error::File {
+            path: path.to_string(),
+            errore,
+        };
+        let mut valori = HashMap::new();
+        let file = File::open(path).map_err(errore_file)?;
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(errore_file)?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let campi = line.replace(",", " ");
+            let mut iter = campi.split_whitespace();
+            match (iter.next(), iter.next().map(str::parse::<i32>)) {
+                (Some(etichetta), Some(Ok(valore))) => {
+                    valori.insert(grafo.stato(etichetta)?, valore);
+                }
+                _ => {
+                    return Err(Error::MalformedLine {
+                        path: path.to_string(),
+                        riga: i + 1,
+                        contenuto: line,
+                    })
+                }
+            }
+        }
+        Ok(FileHeuristic { valori })
+    }
+}
+
+impl Heuristic for FileHeuristic {
+    fn h(&self, state: &State) -> i32 {
+        self.valori.get(state).cloned().unwrap_or(0)
+    }
+}
+
+/// Euclidean distance between the coordinates of a state and the ones of the goal, multiplied
+/// by `scala`: admissible when the scaled distance never exceeds the cost of the path.
+/// The states without coordinates get 0.
+pub struct EuclideanHeuristic {
+    pub coordinate: Vec<Option<(f64, f64)>>,
+    pub scala: f64,
+    pub stato_finale: State,
+}
+
+impl Heuristic for EuclideanHeuristic {
+    fn h(&self, state: &State) -> i32 {
+        match (
+            self.coordinate[*state as usize],
+            self.coordinate[self.stato_finale as usize],
+        ) {
+            (Some((x1, y1)), Some((x2, y2))) => {
+                ((x1 - x2).hypot(y1 - y2) * self.scala).floor() as i32
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Dijkstra from `sorgente` over every state of the graph, following the edges forwards
+/// when `avanti` is true and backwards otherwise; unreachable states get `i32::MAX`, as the
+/// distances that saturate at it.
+fn distanze(grafo: &Graph, sorgente: State, avanti: bool) -> Vec<i32> {
+    let mut distanze = vec![i32::MAX; grafo.num_nodi()];
+    let mut frontiera = BinaryHeap::new();
+    distanze[sorgente as usize] = 0;
+    frontiera.push(Reverse((0, sorgente)));
+    while let Some(Reverse((d, s))) = frontiera.pop() {
+        if d > distanze[s as usize] {
+            continue;
+        }
+        let mut rilassa = |t: State, costo: i32| {
+            let nuova = d.saturating_add(costo);
+            if nuova < distanze[t as usize] {
+                distanze[t as usize] = nuova;
+                frontiera.push(Reverse((nuova, t)));
+            }
+        };
+        if avanti {
+            for (&t, &costo) in grafo.vicini(s).iter().zip(grafo.costi(s)) {
+                rilassa(t, costo);
+            }
+        } else {
+            for azione in grafo.azioni_inverse(s) {
+                rilassa(azione.risultato, azione.costo);
+            }
+        }
+    }
+    distanze
+}
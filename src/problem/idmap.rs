@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use problem::node::*;
+
+/// Bidirectional mapping between the labels of the nodes in a dataset and the dense states
+/// `0..len()` of the graph, assigned in order of first appearance.
+/// Numeric labels are kept as integers and switch to strings at the first label that is not.
+#[derive(Debug, Clone)]
+pub enum IdMap {
+    Numeriche {
+        etichette: Vec<u64>,
+        stati: HashMap<u64, State>,
+    },
+    Testuali {
+        etichette: Vec<String>,
+        stati: HashMap<String, State>,
+    },
+}
+
+impl Default for IdMap {
+    fn default() -> Self {
+        IdMap::new()
+    }
+}
+
+impl IdMap {
+    pub fn new() -> Self {
+        IdMap::Numeriche {
+            etichette: Vec::new(),
+            stati: HashMap::new(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        match self {
+            IdMap::Numeriche { etichette, .. } => etichette.len(),
+            IdMap::Testuali { etichette, .. } => etichette.len(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The state of `etichetta`, added as a new state when it's not in the map yet
+    pub fn inserisci(&mut self, etichetta: &str) -> State {
+        if let IdMap::Numeriche { etichette, stati } = self {
+            if let Some(numero) = numerica(etichetta) {
+                let nuovo = etichette.len() as State;
+                let stato = *stati.entry(numero).or_insert(nuovo);
+                if stato == nuovo {
+                    etichette.push(numero);
+                }
+                return stato;
+            }
+            self.in_testuali();
+        }
+        match self {
+            IdMap::Testuali { etichette, stati } => {
+                if let Some(&stato) = stati.get(etichetta) {
+                    return stato;
+                }
+                let stato = etichette.len() as State;
+                etichette.push(etichetta.to_string());
+                stati.insert(etichetta.to_string(), stato);
+                stato
+            }
+            IdMap::Numeriche { .. } => unreachable!(),
+        }
+    }
+    pub fn stato(&self, etichetta: &str) -> Option<State> {
+        match self {
+            IdMap::Numeriche { stati, .. } => stati.get(&numerica(etichetta)?).cloned(),
+            IdMap::Testuali { stati, .. } => stati.get(etichetta).cloned(),
+        }
+    }
+    pub fn etichetta(&self, stato: State) -> String {
+        match self {
+            IdMap::Numeriche { etichette, .. } => etichette[stato as usize].to_string(),
+            IdMap::Testuali { etichette, .. } => etichette[stato as usize].clone(),
+        }
+    }
+    /// Converts the numeric labels read so far into strings
+    fn in_testuali(&mut self) {
+        if let IdMap::Numeriche { etichette, .. } = self {
+            let etichette: Vec<String> = etichette.iter().map(u64::to_string).collect();
+            let stati = etichette
+                .iter()
+                .enumerate()
+                .map(|(stato, etichetta)| (etichetta.clone(), stato as State))
+                .collect();
+            *self = IdMap::Testuali { etichette, stati };
+        }
+    }
+}
+
+/// The value of a label written as a plain integer: "007" or "+7" are kept as strings,
+/// so that every label is printed back as it was read
+fn numerica(etichetta: &str) -> Option<u64> {
+    if etichetta.starts_with('+') || (etichetta.len() > 1 && etichetta.starts_with('0')) {
+        return None;
+    }
+    etichetta.parse().ok()
+}
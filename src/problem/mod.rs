@@ -1,284 +1,136 @@
+pub mod binary;
+pub mod compression;
+pub mod export;
+pub mod formats;
 pub mod graph;
+pub mod heuristic;
+pub mod idmap;
 pub mod node;
-use std::collections::{HashMap, VecDeque};
+pub mod search;
+pub mod solution;
+pub mod stats;
 use std::fmt;
+use std::hash::Hash;
+use std::iter;
 
 use clap::ValueEnum;
 
-use graph::*;
-use node::*;
+use self::graph::*;
+use self::node::*;
+use self::solution::*;
+use error::*;
 
 #[derive(PartialEq)]
-pub enum SearchResult {
-    Found(Node),
+pub enum SearchResult<S = State> {
+    Found(Solution<S>),
     Failure,
     CutOff,
+    /// The strategy requires non-negative action costs
+    NegativeCost,
+    /// States of a cycle with negative cost reachable from the initial state,
+    /// the first state is repeated at the end
+    NegativeCycle(Vec<S>),
+    /// The strategy cannot be applied to the problem
+    Unsupported,
+    /// The tree search expanded as many nodes as allowed without reaching a goal
+    BudgetExceeded,
 }
 
+/// State space explored by the strategies of `Search`
+pub trait SearchProblem {
+    type State: Hash + Eq + Clone;
+
+    fn stato_iniziale(&self) -> Self::State;
+    fn goal_test(&self, stato: &Self::State) -> bool;
+    /// States reachable from `stato` with a single action, with the cost of the action
+    fn successori(&self, stato: &Self::State) -> impl Iterator<Item = Action<Self::State>>;
+    /// States from which `stato` is reachable with a single action, `None` when the problem
+    /// cannot follow its actions backwards: the bi-directional search is then unsupported
+    fn predecessori(
+        &self,
+        _stato: &Self::State,
+    ) -> Option<impl Iterator<Item = Action<Self::State>>> {
+        None::<iter::Empty<_>>
+    }
+    /// The only goal state, when there is one: needed by the bi-directional search
+    fn stato_finale(&self) -> Option<Self::State> {
+        None
+    }
+    fn ha_costi_negativi(&self) -> bool {
+        false
+    }
+    /// How `stato` is shown to the user
+    fn etichetta(&self, stato: &Self::State) -> String
+    where
+        Self::State: fmt::Display,
+    {
+        stato.to_string()
+    }
+}
+
+/// Path between two states of a graph loaded from a dataset
 pub struct Problem {
     stato_iniziale: State,
     stato_finale: State,
     grafo: Graph,
-    limite: usize,
 }
 impl Problem {
-    pub fn new(stato_iniziale: State, stato_finale: State, dataset_path: &str) -> Self {
-        let grafo = Graph::from_file(&dataset_path);
-        if stato_iniziale as usize >= grafo.nodi().len() {
-            panic!("Stato iniziale non valido");
-        }
-        if stato_finale as usize >= grafo.nodi().len() {
-            panic!("Stato finale non valido");
+    /// The initial and final states are given with their labels in the dataset
+    pub fn new(iniziale: &str, finale: &str, dataset_path: &str) -> Result<Self, Error> {
+        let grafo = Graph::from_file(dataset_path)?;
+        Problem::from_graph(grafo.stato(iniziale)?, grafo.stato(finale)?, grafo)
+    }
+    pub fn from_graph(
+        stato_iniziale: State,
+        stato_finale: State,
+        grafo: Graph,
+    ) -> Result<Self, Error> {
+        for &stato in &[stato_iniziale, stato_finale] {
+            grafo.controlla(stato)?;
+            // an isolated state can only be the goal of a search starting from it
+            if stato_iniziale != stato_finale && grafo.is_isolated(stato) {
+                return Err(Error::UnreachableState(stato));
+            }
         }
-        Problem {
+        Ok(Problem {
             stato_iniziale,
             stato_finale,
             grafo,
-            limite: 10,
-        }
-    }
-
-    pub fn goal_test(&self, stato: &State) -> bool {
-        *stato == self.stato_finale
-    }
-
-    pub fn get_stato_iniziale(&self) -> State {
-        self.stato_iniziale
+        })
     }
 
     pub fn get_stato_finale(&self) -> State {
         self.stato_finale
     }
-    #[allow(dead_code)]
-    pub fn tree_search(&mut self) -> SearchResult {
-        let mut frontiera = VecDeque::new();
-        frontiera.push_back(Node {
-            stato: self.get_stato_iniziale(),
-            azioni: self.grafo.nodi()[self.get_stato_iniziale() as usize]
-                .azioni
-                .clone(),
-            genitore: None,
-            costo_cammino: 0,
-            profondita: 0,
-        });
-        while let Some(nodo) = frontiera.pop_front() {
-            if self.goal_test(&nodo.stato) {
-                return SearchResult::Found(nodo);
-            }
-            frontiera.extend(self.espandi(&nodo));
-        }
-        SearchResult::Failure
-    }
-    pub fn breadth_first_search(&mut self) -> SearchResult {
-        let nodo = Node {
-            stato: self.get_stato_iniziale(),
-            azioni: self.grafo.nodi()[self.get_stato_iniziale() as usize]
-                .azioni
-                .clone(),
-            genitore: None,
-            costo_cammino: 0,
-            profondita: 0,
-        };
-
-        if self.goal_test(&nodo.stato) {
-            return SearchResult::Found(nodo);
-        }
 
-        let mut frontiera = VecDeque::new();
-        frontiera.push_back(nodo.clone());
-
-        let mut raggiunti = HashMap::new();
-        raggiunti.insert(self.get_stato_iniziale(), nodo);
-
-        // checks if the frontiera is empty and assigns the popped value to nodo at the same time
-        while let Some(nodo) = frontiera.pop_front() {
-            for figlio in self.espandi(&nodo) {
-                let s = figlio.stato;
-                if self.goal_test(&s) {
-                    return SearchResult::Found(figlio);
-                }
-                // since raggiunti is a HashMap, we can use the insert method to check if the key already exists
-                if raggiunti.insert(s, figlio.clone()).is_none() {
-                    frontiera.push_back(figlio);
-                }
-            }
-        }
-
-        SearchResult::Failure
+    pub fn grafo(&self) -> &Graph {
+        &self.grafo
     }
-    pub fn uniform_cost_search(&mut self) -> SearchResult {
-        let nodo = Node {
-            stato: self.get_stato_iniziale(),
-            azioni: self.grafo.nodi()[self.get_stato_iniziale() as usize]
-                .azioni
-                .clone(),
-            genitore: None,
-            costo_cammino: 0,
-            profondita: 0,
-        };
-
-        if self.goal_test(&nodo.stato) {
-            return SearchResult::Found(nodo);
-        }
-
-        let mut frontiera = VecDeque::new();
-        frontiera.push_back(nodo.clone());
+}
 
-        let mut raggiunti = HashMap::new();
-        raggiunti.insert(self.get_stato_iniziale(), nodo);
+impl SearchProblem for Problem {
+    type State = State;
 
-        // checks if the frontiera is empty and assigns the popped value to nodo at the same time
-        while let Some(nodo) = frontiera.pop_front() {
-            for figlio in self.espandi(&nodo) {
-                let s = figlio.stato;
-                if self.goal_test(&s) {
-                    return SearchResult::Found(figlio);
-                }
-                if raggiunti.get(&s).is_none() || raggiunti[&s].costo_cammino > figlio.costo_cammino
-                {
-                    raggiunti.insert(s, figlio.clone());
-                    frontiera.push_back(figlio);
-                }
-            }
-        }
-        SearchResult::Failure
+    fn stato_iniziale(&self) -> State {
+        self.stato_iniziale
     }
-    pub fn depth_limited_search(&mut self) -> SearchResult {
-        self.recursive_depth_limited(
-            Node {
-                stato: self.get_stato_iniziale(),
-                azioni: self.grafo.nodi()[self.get_stato_iniziale() as usize]
-                    .azioni
-                    .clone(),
-                genitore: None,
-                costo_cammino: 0,
-                profondita: 0,
-            },
-            self.limite,
-        )
+    fn goal_test(&self, stato: &State) -> bool {
+        *stato == self.stato_finale
     }
-    fn recursive_depth_limited(&mut self, nodo: Node, limite: usize) -> SearchResult {
-        let mut cutoff = false;
-        if self.goal_test(&nodo.stato) {
-            return SearchResult::Found(nodo);
-        } else if nodo.profondita == limite {
-            return SearchResult::CutOff;
-        } else {
-            for figlio in self.espandi(&nodo) {
-                let result = self.recursive_depth_limited(figlio, limite);
-                if result == SearchResult::CutOff {
-                    cutoff = true;
-                } else if result != SearchResult::Failure {
-                    return result;
-                }
-            }
-        }
-        if cutoff {
-            SearchResult::CutOff
-        } else {
-            SearchResult::Failure
-        }
+    fn successori(&self, stato: &State) -> impl Iterator<Item = Action> {
+        self.grafo.azioni(*stato)
     }
-    pub fn iterative_deepening_search(&mut self) -> SearchResult {
-        self.limite = 1;
-        loop {
-            let result = self.depth_limited_search();
-            if result != SearchResult::CutOff {
-                return result;
-            }
-            if result == SearchResult::Failure {
-                break;
-            }
-            self.limite += 1;
-        }
-
-        SearchResult::Failure
+    fn predecessori(&self, stato: &State) -> Option<impl Iterator<Item = Action>> {
+        Some(self.grafo.azioni_inverse(*stato))
     }
-    pub fn bi_directional_search(&mut self) -> SearchResult {
-        let mut nodo_iniziale = Node {
-            stato: self.get_stato_iniziale(),
-            azioni: self.grafo.nodi()[self.get_stato_iniziale() as usize]
-                .azioni
-                .clone(),
-            genitore: None,
-            costo_cammino: 0,
-            profondita: 0,
-        };
-        let mut nodo_finale = Node {
-            stato: self.get_stato_finale(),
-            azioni: self.grafo.nodi()[self.get_stato_finale() as usize]
-                .azioni
-                .clone(),
-            genitore: None,
-            costo_cammino: 0,
-            profondita: 0,
-        };
-
-        if self.goal_test(&nodo_iniziale.stato) {
-            return SearchResult::Found(nodo_iniziale);
-        }
-
-        let mut frontiera_iniziale = VecDeque::new();
-        let mut frontiera_finale = VecDeque::new();
-        frontiera_iniziale.push_back(nodo_iniziale.clone());
-        frontiera_finale.push_back(nodo_finale.clone());
-
-        let mut raggiunti_iniziale = HashMap::new();
-        let mut raggiunti_finale = HashMap::new();
-        raggiunti_iniziale.insert(self.get_stato_iniziale(), nodo_iniziale);
-        raggiunti_finale.insert(self.get_stato_finale(), nodo_finale);
-
-        while !frontiera_iniziale.is_empty() && !frontiera_finale.is_empty() {
-            nodo_iniziale = frontiera_iniziale.pop_front().unwrap();
-            nodo_finale = frontiera_finale.pop_front().unwrap();
-            for figlio in self.espandi(&nodo_iniziale) {
-                let s = figlio.stato;
-                // since raggiunti is a HashMap, we can use the insert method to check if the key already exists
-                if raggiunti_iniziale.insert(s, figlio.clone()).is_none() {
-                    frontiera_iniziale.push_back(figlio);
-                }
-            }
-            for figlio in self.espandi(&nodo_finale) {
-                let s = figlio.stato;
-                // since raggiunti is a HashMap, we can use the insert method to check if the key already exists
-                if raggiunti_finale.insert(s, figlio.clone()).is_none() {
-                    frontiera_finale.push_back(figlio);
-                }
-            }
-            // check if the two frontiers have a common node
-            if let Some(nodo) = raggiunti_iniziale
-                .keys()
-                .find(|&k| raggiunti_finale.contains_key(k))
-            {
-                let mut nodo = raggiunti_finale[nodo].clone();
-                let nodo_comune = nodo.clone();
-                let mut profondita = 1;
-                while let Some(genitore) = &nodo.genitore {
-                    nodo = *genitore.clone();
-                    profondita += 1;
-                    nodo.profondita = nodo_comune.profondita + profondita;
-                    nodo.costo_cammino = nodo_comune.costo_cammino + nodo.costo_cammino;
-                }
-                return SearchResult::Found(nodo);
-            }
-        }
-        SearchResult::Failure
+    fn stato_finale(&self) -> Option<State> {
+        Some(self.stato_finale)
     }
-    fn espandi(&self, nodo: &Node) -> impl Iterator<Item = Node> {
-        let mut yield_nodo = Vec::new();
-        let _s = nodo.stato;
-        for azione in &nodo.azioni {
-            let s1 = azione.risultato;
-            let costo = nodo.costo_cammino + azione.costo;
-            yield_nodo.push(Node {
-                stato: s1,
-                azioni: self.grafo.nodi()[s1 as usize].azioni.clone(),
-                genitore: Some(Box::new(nodo.clone())),
-                costo_cammino: costo,
-                profondita: nodo.profondita + 1,
-            });
-        }
-        yield_nodo.into_iter()
+    fn ha_costi_negativi(&self) -> bool {
+        self.grafo.has_negative_costs()
+    }
+    fn etichetta(&self, stato: &State) -> String {
+        self.grafo.etichetta(*stato)
     }
 }
 
@@ -290,6 +142,11 @@ pub enum Ricerca {
     DepthLimited,
     IterativeDeepening,
     BiDirectional,
+    AStar,
+    GreedyBestFirst,
+    BellmanFord,
+    IdaStar,
+    RecursiveBestFirst,
 }
 impl fmt::Display for Ricerca {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -300,6 +157,19 @@ impl fmt::Display for Ricerca {
             Ricerca::DepthLimited => write!(f, "depth-limited"),
             Ricerca::IterativeDeepening => write!(f, "iterative-deepening"),
             Ricerca::BiDirectional => write!(f, "bi-directional"),
+            Ricerca::AStar => write!(f, "a-star"),
+            Ricerca::GreedyBestFirst => write!(f, "greedy-best-first"),
+            Ricerca::BellmanFord => write!(f, "bellman-ford"),
+            Ricerca::IdaStar => write!(f, "ida-star"),
+            Ricerca::RecursiveBestFirst => write!(f, "recursive-best-first"),
         }
     }
 }
+
+/// How a strategy treats the states it reaches again: a tree search keeps every path as a
+/// distinct node, a graph search discards the paths that are not better than a known one
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum Modalita {
+    Tree,
+    Graph,
+}
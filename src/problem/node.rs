@@ -1,15 +1,102 @@
+use std::ops::Index;
+
+use problem::solution::*;
+
 pub type State = u32;
+/// Index of a node inside a `SearchTree`
+pub type NodeId = usize;
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
-pub struct Action {
-    pub risultato: State,
+pub struct Action<S = State> {
+    pub risultato: S,
     pub costo: i32,
 }
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
-pub struct Node {
-    pub stato: State,
-    pub azioni: Vec<Action>,
-    pub genitore: Option<Box<Node>>,
+pub struct Node<S = State> {
+    pub stato: S,
+    pub genitore: Option<NodeId>,
     pub costo_cammino: i32,
     pub profondita: usize,
 }
 
+/// Arena holding the nodes generated by a search: each node references its parent
+/// by index, so expanding a node never copies its ancestors.
+#[derive(Debug)]
+pub struct SearchTree<S = State> {
+    nodi: Vec<Node<S>>,
+    /// `espansi[id]` is true once the children of the node `id` have been generated
+    espansi: Vec<bool>,
+}
+
+impl<S: Clone> Default for SearchTree<S> {
+    fn default() -> Self {
+        SearchTree::new()
+    }
+}
+
+impl<S: Clone> SearchTree<S> {
+    pub fn new() -> Self {
+        SearchTree {
+            nodi: Vec::new(),
+            espansi: Vec::new(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.nodi.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodi.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.nodi.clear();
+        self.espansi.clear();
+    }
+    /// Drops every node with an id greater or equal than `len`
+    pub fn truncate(&mut self, len: usize) {
+        self.nodi.truncate(len);
+        self.espansi.truncate(len);
+    }
+    pub fn aggiungi(&mut self, nodo: Node<S>) -> NodeId {
+        self.nodi.push(nodo);
+        self.espansi.push(false);
+        self.nodi.len() - 1
+    }
+    pub fn segna_espanso(&mut self, id: NodeId) {
+        self.espansi[id] = true;
+    }
+    pub fn is_espanso(&self, id: NodeId) -> bool {
+        self.espansi[id]
+    }
+    pub fn radice(&mut self, stato: S) -> NodeId {
+        self.aggiungi(Node {
+            stato,
+            genitore: None,
+            costo_cammino: 0,
+            profondita: 0,
+        })
+    }
+    /// Path from the root of the tree to the node `id`, walking the parent ids
+    pub fn soluzione(&self, id: NodeId) -> Solution<S> {
+        let mut soluzione = Solution::default();
+        let mut corrente = id;
+        while let Some(genitore) = self[corrente].genitore {
+            soluzione.push(
+                self[corrente].stato.clone(),
+                self[corrente]
+                    .costo_cammino
+                    .saturating_sub(self[genitore].costo_cammino),
+            );
+            corrente = genitore;
+        }
+        soluzione.stati.push(self[corrente].stato.clone());
+        soluzione.stati.reverse();
+        soluzione.costi.reverse();
+        soluzione
+    }
+}
+
+impl<S> Index<NodeId> for SearchTree<S> {
+    type Output = Node<S>;
+    fn index(&self, id: NodeId) -> &Node<S> {
+        &self.nodi[id]
+    }
+}
@@ -0,0 +1,912 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use problem::heuristic::*;
+use problem::node::*;
+use problem::solution::*;
+use problem::*;
+
+/// States met by a search: the expanded ones, and the frontier of the states that were
+/// generated but never expanded
+#[derive(Debug, Clone, PartialEq)]
+pub struct Esplorazione<S: Hash + Eq = State> {
+    pub espansi: HashSet<S>,
+    pub frontiera: HashSet<S>,
+}
+
+impl<S: Hash + Eq> Default for Esplorazione<S> {
+    fn default() -> Self {
+        Esplorazione {
+            espansi: HashSet::new(),
+            frontiera: HashSet::new(),
+        }
+    }
+}
+
+impl<S: Hash + Eq> Esplorazione<S> {
+    /// Every reached state, expanded or in the frontier
+    pub fn raggiunti(&self) -> impl Iterator<Item = &S> {
+        self.espansi.iter().chain(self.frontiera.iter())
+    }
+}
+
+/// Work and memory of a strategy, returned together with its result
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchStats {
+    pub espansi: usize,
+    /// Children generated by the expansions, the root excluded
+    pub generati: usize,
+    /// Generated children discarded because their state was already reached by a path that is
+    /// not worse, or is on the current path
+    pub duplicati: usize,
+    /// Largest number of nodes waiting to be expanded at once
+    pub frontiera_massima: usize,
+    /// Largest number of states in the reached set at once; the tree searches, that have none,
+    /// count the nodes in the search tree
+    pub raggiunti_massimi: usize,
+    /// Depth limits tried by `iterative_deepening_search`, f bounds by `ida_star_search`,
+    /// forgotten subtrees searched again by `recursive_best_first_search`; 0 for the others
+    pub iterazioni: usize,
+    /// Expansions of the linear-space strategies repeated from a previous iteration, for nodes
+    /// within the f already explored
+    pub riespansioni: usize,
+}
+
+/// Node on the path of `recursive_best_first_search`, with its children and their f
+struct Chiamata<S> {
+    id: NodeId,
+    limite: i32,
+    figli: Vec<Successore<S>>,
+    /// f of the forgotten subtree this node is part of, searched again
+    gia_esplorato: Option<i32>,
+}
+
+struct Successore<S> {
+    nodo: Node<S>,
+    f: i32,
+    /// The subtree of the node was searched and forgotten
+    esplorato: bool,
+}
+
+/// The child with the lowest f, the first one on ties, and the f of the second best
+fn migliori<S>(figli: &[Successore<S>]) -> (usize, i32) {
+    let mut migliore = 0;
+    for (i, figlio) in figli.iter().enumerate() {
+        if figlio.f < figli[migliore].f {
+            migliore = i;
+        }
+    }
+    let alternativa = figli
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != migliore)
+        .map(|(_, figlio)| figlio.f)
+        .min()
+        .unwrap_or(i32::MAX);
+    (migliore, alternativa)
+}
+
+/// Lowest cost at which each state was generated, and the state it was generated from
+type Generati<S> = HashMap<S, (i32, S)>;
+
+/// In graph mode the linear-space strategies skip a node when its state was generated before
+/// with a lower cost, or with the same cost from another state: a forgotten subtree searched
+/// again generates its nodes from the same states, so it is not skipped
+fn ripetuto<S: Hash + Eq + Clone>(
+    generati: &mut Generati<S>,
+    nodo: &Node<S>,
+    genitore: &S,
+) -> bool {
+    match generati.get(&nodo.stato) {
+        Some((costo, da))
+            if *costo < nodo.costo_cammino || (*costo == nodo.costo_cammino && da != genitore) =>
+        {
+            true
+        }
+        _ => {
+            generati.insert(nodo.stato.clone(), (nodo.costo_cammino, genitore.clone()));
+            false
+        }
+    }
+}
+
+/// Search strategies, shared by every `SearchProblem`
+pub struct Search<'a, P: SearchProblem + 'a> {
+    problema: &'a P,
+    limite: usize,
+    profondita_massima: usize,
+    controllo_cicli: bool,
+    modalita: Option<Modalita>,
+    espansioni_massime: usize,
+    /// Whether the strategy being run is bounded by the budget
+    limitata: bool,
+    statistiche: SearchStats,
+    albero: SearchTree<P::State>,
+    euristica: Box<dyn Heuristic<P::State> + 'a>,
+}
+
+impl<'a, P: SearchProblem> Search<'a, P> {
+    pub fn new(problema: &'a P) -> Self {
+        Search {
+            problema,
+            limite: 10,
+            profondita_massima: 50,
+            controllo_cicli: false,
+            modalita: None,
+            espansioni_massime: 1_000_000,
+            limitata: false,
+            statistiche: SearchStats::default(),
+            albero: SearchTree::new(),
+            euristica: Box::new(ZeroHeuristic),
+        }
+    }
+
+    /// Depth at which `depth_limited_search` cuts off the nodes
+    pub fn set_limite(&mut self, limite: usize) {
+        self.limite = limite;
+    }
+    /// Largest limit tried by `iterative_deepening_search`, 50 by default: on cyclic graphs an
+    /// unreachable goal is cut off at every depth unless the cycle check is enabled
+    pub fn set_profondita_massima(&mut self, profondita_massima: usize) {
+        self.profondita_massima = profondita_massima;
+    }
+    /// When true, the depth-limited strategies skip the states already on the current path
+    pub fn set_controllo_cicli(&mut self, controllo_cicli: bool) {
+        self.controllo_cicli = controllo_cicli;
+    }
+    /// Runs every strategy as a tree or graph search; by default `tree_search`, the
+    /// depth-limited and the linear-space strategies are tree searches, the others graph searches
+    pub fn set_modalita(&mut self, modalita: Modalita) {
+        self.modalita = Some(modalita);
+    }
+    /// Nodes a tree search may expand before giving up, 1000000 by default
+    pub fn set_espansioni_massime(&mut self, espansioni_massime: usize) {
+        self.espansioni_massime = espansioni_massime;
+    }
+    /// Heuristic used by the informed strategies
+    pub fn set_euristica(&mut self, euristica: Box<dyn Heuristic<P::State> + 'a>) {
+        self.euristica = euristica;
+    }
+    /// States reached by the last strategy that was run, from the nodes left in its search tree:
+    /// the depth-limited strategies drop the subtrees they have fully explored
+    pub fn esplorazione(&self) -> Esplorazione<P::State> {
+        let mut esplorazione = Esplorazione::default();
+        for id in 0..self.albero.len() {
+            if self.albero.is_espanso(id) {
+                esplorazione.espansi.insert(self.albero[id].stato.clone());
+            }
+        }
+        for id in 0..self.albero.len() {
+            let s = &self.albero[id].stato;
+            if !esplorazione.espansi.contains(s) {
+                esplorazione.frontiera.insert(s.clone());
+            }
+        }
+        esplorazione
+    }
+    /// Whether the strategy runs as a graph search, `predefinita` being its own mode
+    fn in_grafo(&self, predefinita: Modalita) -> bool {
+        self.modalita.unwrap_or(predefinita) == Modalita::Graph
+    }
+    /// Clears the search tree and the statistics for a new strategy; a tree search is bounded
+    /// by the budget
+    fn inizia(&mut self, grafo: bool) {
+        self.albero.clear();
+        self.statistiche = SearchStats::default();
+        self.limitata = !grafo;
+    }
+    fn esaurito(&self) -> bool {
+        self.limitata && self.statistiche.espansi >= self.espansioni_massime
+    }
+    /// Updates the peaks with the current size of the frontier and of the reached set,
+    /// `None` for the tree searches
+    fn misura(&mut self, frontiera: usize, raggiunti: Option<usize>) {
+        let raggiunti = raggiunti.unwrap_or_else(|| self.albero.len());
+        let statistiche = &mut self.statistiche;
+        statistiche.frontiera_massima = statistiche.frontiera_massima.max(frontiera);
+        statistiche.raggiunti_massimi = statistiche.raggiunti_massimi.max(raggiunti);
+    }
+    /// Runs `strategia` on a new search, returning its result with the statistics it collected
+    fn con_statistiche<F>(&mut self, strategia: F) -> (SearchResult<P::State>, SearchStats)
+    where
+        F: FnOnce(&mut Self) -> SearchResult<P::State>,
+    {
+        let risultato = strategia(self);
+        (risultato, self.statistiche.clone())
+    }
+    /// Breadth-first search testing the goal on pop; as a graph search it is the same as
+    /// `breadth_first_search`, but for the late goal test
+    pub fn tree_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(Self::su_albero)
+    }
+    fn su_albero(&mut self) -> SearchResult<P::State> {
+        let grafo = self.in_grafo(Modalita::Tree);
+        self.inizia(grafo);
+        let mut frontiera = VecDeque::new();
+        frontiera.push_back(self.albero.radice(self.problema.stato_iniziale()));
+        let mut raggiunti = HashSet::new();
+        raggiunti.insert(self.problema.stato_iniziale());
+        while let Some(id) = frontiera.pop_front() {
+            if self.problema.goal_test(&self.albero[id].stato) {
+                return SearchResult::Found(self.albero.soluzione(id));
+            } else if self.esaurito() {
+                return SearchResult::BudgetExceeded;
+            }
+            for figlio in self.espandi(id) {
+                if grafo && !raggiunti.insert(figlio.stato.clone()) {
+                    self.statistiche.duplicati += 1;
+                    continue;
+                }
+                frontiera.push_back(self.albero.aggiungi(figlio));
+            }
+            self.misura(frontiera.len(), grafo.then_some(raggiunti.len()));
+        }
+        SearchResult::Failure
+    }
+    pub fn breadth_first_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(Self::in_ampiezza)
+    }
+    fn in_ampiezza(&mut self) -> SearchResult<P::State> {
+        let grafo = self.in_grafo(Modalita::Graph);
+        self.inizia(grafo);
+        let radice = self.albero.radice(self.problema.stato_iniziale());
+
+        if self.problema.goal_test(&self.albero[radice].stato) {
+            return SearchResult::Found(self.albero.soluzione(radice));
+        }
+
+        let mut frontiera = VecDeque::new();
+        frontiera.push_back(radice);
+
+        let mut raggiunti = HashSet::new();
+        raggiunti.insert(self.problema.stato_iniziale());
+
+        // checks if the frontiera is empty and assigns the popped value to id at the same time
+        while let Some(id) = frontiera.pop_front() {
+            if self.esaurito() {
+                return SearchResult::BudgetExceeded;
+            }
+            for figlio in self.espandi(id) {
+                if self.problema.goal_test(&figlio.stato) {
+                    let figlio = self.albero.aggiungi(figlio);
+                    return SearchResult::Found(self.albero.soluzione(figlio));
+                }
+                // as a graph search, only nodes reaching a new state are stored in the search tree
+                if grafo && !raggiunti.insert(figlio.stato.clone()) {
+                    self.statistiche.duplicati += 1;
+                    continue;
+                }
+                frontiera.push_back(self.albero.aggiungi(figlio));
+            }
+            self.misura(frontiera.len(), grafo.then_some(raggiunti.len()));
+        }
+
+        SearchResult::Failure
+    }
+    /// Dijkstra's algorithm: the returned cost is optimal as long as no action has a negative cost
+    pub fn uniform_cost_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(|ricerca| ricerca.best_first_search(|nodo, _| nodo.costo_cammino))
+    }
+    /// Depth-first search that cuts off the nodes at depth `limite`, with an explicit stack
+    pub fn depth_limited_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(|ricerca| {
+            let grafo = ricerca.in_grafo(Modalita::Tree);
+            ricerca.inizia(grafo);
+            ricerca.profondita_limitata(grafo)
+        })
+    }
+    /// As a graph search, a state is skipped when it was already reached at the same depth or
+    /// above, since its subtree was explored at least as deep
+    fn profondita_limitata(&mut self, grafo: bool) -> SearchResult<P::State> {
+        self.albero.clear();
+        let radice = self.albero.radice(self.problema.stato_iniziale());
+        if self.problema.goal_test(&self.albero[radice].stato) {
+            return SearchResult::Found(self.albero.soluzione(radice));
+        } else if self.limite == 0 {
+            return SearchResult::CutOff;
+        }
+        let mut cutoff = false;
+        // the states on the path to the top of the stack, only kept for the cycle check
+        let mut cammino = HashSet::new();
+        if self.controllo_cicli {
+            cammino.insert(self.problema.stato_iniziale());
+        }
+        let mut raggiunti = HashMap::new();
+        raggiunti.insert(self.problema.stato_iniziale(), 0);
+        // every node on the current path, with the children still to visit
+        let figli = self.espandi(radice);
+        let mut aperti = figli.len();
+        let mut pila = vec![(radice, figli)];
+        self.misura(aperti, grafo.then_some(raggiunti.len()));
+        while let Some((id, figli)) = pila.last_mut() {
+            let id = *id;
+            let figlio = match figli.next() {
+                Some(figlio) => {
+                    aperti -= 1;
+                    figlio
+                }
+                None => {
+                    pila.pop();
+                    if self.controllo_cicli {
+                        cammino.remove(&self.albero[id].stato);
+                    }
+                    // the subtree has been fully explored, only the current path is kept
+                    if !pila.is_empty() {
+                        self.albero.truncate(id);
+                    }
+                    continue;
+                }
+            };
+            if self.controllo_cicli && cammino.contains(&figlio.stato) {
+                self.statistiche.duplicati += 1;
+                continue;
+            }
+            if grafo {
+                match raggiunti.entry(figlio.stato.clone()) {
+                    Entry::Occupied(entry) if *entry.get() <= figlio.profondita => {
+                        self.statistiche.duplicati += 1;
+                        continue;
+                    }
+                    entry => {
+                        *entry.or_insert(0) = figlio.profondita;
+                    }
+                }
+            }
+            let figlio = self.albero.aggiungi(figlio);
+            if self.problema.goal_test(&self.albero[figlio].stato) {
+                return SearchResult::Found(self.albero.soluzione(figlio));
+            } else if self.albero[figlio].profondita == self.limite {
+                cutoff = true;
+                self.albero.truncate(figlio);
+            } else if self.esaurito() {
+                return SearchResult::BudgetExceeded;
+            } else {
+                if self.controllo_cicli {
+                    cammino.insert(self.albero[figlio].stato.clone());
+                }
+                let figli = self.espandi(figlio);
+                aperti += figli.len();
+                pila.push((figlio, figli));
+                self.misura(aperti, grafo.then_some(raggiunti.len()));
+            }
+        }
+        if cutoff {
+            SearchResult::CutOff
+        } else {
+            SearchResult::Failure
+        }
+    }
+    /// Depth-limited searches with limits from 0 to `profondita_massima`: the result is a
+    /// cutoff only when the last one was cut off as well. The budget bounds all of them together
+    pub fn iterative_deepening_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(Self::approfondimento_iterativo)
+    }
+    fn approfondimento_iterativo(&mut self) -> SearchResult<P::State> {
+        let grafo = self.in_grafo(Modalita::Tree);
+        self.inizia(grafo);
+        let limite = self.limite;
+        let mut risultato = SearchResult::CutOff;
+        for profondita in 0..=self.profondita_massima {
+            self.limite = profondita;
+            self.statistiche.iterazioni += 1;
+            risultato = self.profondita_limitata(grafo);
+            if risultato != SearchResult::CutOff {
+                break;
+            }
+        }
+        self.limite = limite;
+        risultato
+    }
+    /// Needs a single goal state: the backward half follows `SearchProblem::predecessori`
+    pub fn bi_directional_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(Self::bidirezionale)
+    }
+    fn bidirezionale(&mut self) -> SearchResult<P::State> {
+        // both halves of the search share the same search tree
+        let grafo = self.in_grafo(Modalita::Graph);
+        self.inizia(grafo);
+        let radice_iniziale = self.albero.radice(self.problema.stato_iniziale());
+
+        if self.problema.goal_test(&self.albero[radice_iniziale].stato) {
+            return SearchResult::Found(self.albero.soluzione(radice_iniziale));
+        }
+
+        // the backward half needs a single goal state and the predecessors of the states
+        let stato_finale = match self.problema.stato_finale() {
+            Some(stato) if self.problema.predecessori(&stato).is_some() => stato,
+            _ => return SearchResult::Unsupported,
+        };
+        let radice_finale = self.albero.radice(stato_finale.clone());
+
+        let mut frontiera_iniziale = VecDeque::new();
+        let mut frontiera_finale = VecDeque::new();
+        frontiera_iniziale.push_back(radice_iniziale);
+        frontiera_finale.push_back(radice_finale);
+
+        let mut raggiunti_iniziale = HashMap::new();
+        let mut raggiunti_finale = HashMap::new();
+        raggiunti_iniziale.insert(self.problema.stato_iniziale(), radice_iniziale);
+        raggiunti_finale.insert(stato_finale, radice_finale);
+
+        while !frontiera_iniziale.is_empty() && !frontiera_finale.is_empty() {
+            // a whole layer of the smaller frontier is expanded at every step: since every state
+            // closer to both roots has already been reached, the first meeting is a shortest path
+            let avanti = frontiera_iniziale.len() <= frontiera_finale.len();
+            let altra_frontiera = if avanti {
+                frontiera_finale.len()
+            } else {
+                frontiera_iniziale.len()
+            };
+            let (frontiera, raggiunti, altri) = if avanti {
+                (
+                    &mut frontiera_iniziale,
+                    &mut raggiunti_iniziale,
+                    &raggiunti_finale,
+                )
+            } else {
+                (
+                    &mut frontiera_finale,
+                    &mut raggiunti_finale,
+                    &raggiunti_iniziale,
+                )
+            };
+            for _ in 0..frontiera.len() {
+                if self.esaurito() {
+                    return SearchResult::BudgetExceeded;
+                }
+                let id = frontiera.pop_front().unwrap();
+                for figlio in self.espandi_verso(id, avanti) {
+                    // as a tree search the states reached again are expanded again, the
+                    // halves meet on the first node reaching each state
+                    let nuovo = !raggiunti.contains_key(&figlio.stato);
+                    if grafo && !nuovo {
+                        self.statistiche.duplicati += 1;
+                        continue;
+                    }
+                    let figlio = self.albero.aggiungi(figlio);
+                    if nuovo {
+                        raggiunti.insert(self.albero[figlio].stato.clone(), figlio);
+                    }
+                    if let Some(&altro) = altri.get(&self.albero[figlio].stato) {
+                        return SearchResult::Found(if avanti {
+                            self.unisci(figlio, altro)
+                        } else {
+                            self.unisci(altro, figlio)
+                        });
+                    }
+                    frontiera.push_back(figlio);
+                }
+                self.misura(
+                    frontiera.len() + altra_frontiera,
+                    grafo.then_some(raggiunti.len() + altri.len()),
+                );
+            }
+        }
+        SearchResult::Failure
+    }
+    /// Best-first search on f(n) = g(n) + h(n): the cost is optimal when the heuristic is admissible
+    pub fn a_star_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(|ricerca| {
+            ricerca.best_first_search(|nodo, euristica| {
+                nodo.costo_cammino.saturating_add(euristica.h(&nodo.stato))
+            })
+        })
+    }
+    /// Best-first search on f(n) = h(n). Negative costs are rejected as well: they don't change
+    /// the order of the frontiera, but a cheaper path reopens a state already reached, so a
+    /// negative cycle would be followed forever
+    pub fn greedy_best_first_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(|ricerca| {
+            ricerca.best_first_search(|nodo, euristica| euristica.h(&nodo.stato))
+        })
+    }
+    /// Shared by `uniform_cost_search`, `a_star_search` and `greedy_best_first_search`: the
+    /// frontiera is a min-heap on `f`, given a node and the heuristic, ties are broken by
+    /// insertion order. Negative costs are rejected, as a cheaper path to a reached state
+    /// reopens it
+    fn best_first_search<F>(&mut self, f: F) -> SearchResult<P::State>
+    where
+        F: Fn(&Node<P::State>, &dyn Heuristic<P::State>) -> i32,
+    {
+        let grafo = self.in_grafo(Modalita::Graph);
+        self.inizia(grafo);
+        if self.problema.ha_costi_negativi() {
+            return SearchResult::NegativeCost;
+        }
+        let radice = self.albero.radice(self.problema.stato_iniziale());
+
+        let mut frontiera = BinaryHeap::new();
+        frontiera.push(Reverse((f(&self.albero[radice], &*self.euristica), radice)));
+
+        let mut raggiunti = HashMap::new();
+        raggiunti.insert(self.problema.stato_iniziale(), radice);
+
+        while let Some(Reverse((_, id))) = frontiera.pop() {
+            let s = &self.albero[id].stato;
+            // a cheaper node for the same state was pushed after this one, skip the stale entry
+            if grafo && raggiunti[s] != id {
+                continue;
+            }
+            // the goal is tested on pop: with uniform costs, or an admissible A*, no cheaper path
+            // to it can exist
+            if self.problema.goal_test(s) {
+                return SearchResult::Found(self.albero.soluzione(id));
+            } else if self.esaurito() {
+                return SearchResult::BudgetExceeded;
+            }
+            for figlio in self.espandi(id) {
+                if !grafo
+                    || !raggiunti.contains_key(&figlio.stato)
+                    || self.albero[raggiunti[&figlio.stato]].costo_cammino > figlio.costo_cammino
+                {
+                    let priorita = f(&figlio, &*self.euristica);
+                    let s = figlio.stato.clone();
+                    let figlio = self.albero.aggiungi(figlio);
+                    if grafo {
+                        raggiunti.insert(s, figlio);
+                    }
+                    frontiera.push(Reverse((priorita, figlio)));
+                } else {
+                    self.statistiche.duplicati += 1;
+                }
+            }
+            self.misura(frontiera.len(), grafo.then_some(raggiunti.len()));
+        }
+        SearchResult::Failure
+    }
+    /// Iterative-deepening A*: depth-first searches bounded by f(n) = g(n) + h(n), every one
+    /// with the lowest f that exceeded the previous bound. The cost is optimal when the heuristic
+    /// is admissible; the states on the current path are skipped, so zero-cost cycles end
+    pub fn ida_star_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(Self::per_contorni)
+    }
+    fn per_contorni(&mut self) -> SearchResult<P::State> {
+        let grafo = self.in_grafo(Modalita::Tree);
+        self.inizia(grafo);
+        if self.problema.ha_costi_negativi() {
+            return SearchResult::NegativeCost;
+        }
+        let mut soglia = self.euristica.h(&self.problema.stato_iniziale());
+        let mut precedente = None;
+        loop {
+            self.statistiche.iterazioni += 1;
+            match self.contorno(soglia, precedente, grafo) {
+                Ok(soluzione) => return SearchResult::Found(soluzione),
+                Err(Some(prossima)) => {
+                    precedente = Some(soglia);
+                    soglia = prossima;
+                }
+                Err(None) if self.esaurito() => return SearchResult::BudgetExceeded,
+                Err(None) => return SearchResult::Failure,
+            }
+        }
+    }
+    /// Depth-first search of the nodes with f(n) <= `soglia`; on failure, the lowest f above it,
+    /// none when there is no such node or the budget is used up. The expansions of the nodes
+    /// within the `precedente` bound are repeated
+    fn contorno(
+        &mut self,
+        soglia: i32,
+        precedente: Option<i32>,
+        grafo: bool,
+    ) -> Result<Solution<P::State>, Option<i32>> {
+        self.albero.clear();
+        let radice = self.albero.radice(self.problema.stato_iniziale());
+        if self.problema.goal_test(&self.albero[radice].stato) {
+            return Ok(self.albero.soluzione(radice));
+        }
+        let mut superata: Option<i32> = None;
+        let mut cammino = HashSet::new();
+        cammino.insert(self.problema.stato_iniziale());
+        let mut generati = if grafo { Some(HashMap::new()) } else { None };
+        self.conta_riespansione(precedente.is_some());
+        let figli = self.espandi(radice);
+        let mut aperti = figli.len();
+        let mut pila = vec![(radice, figli)];
+        while let Some((id, figli)) = pila.last_mut() {
+            let id = *id;
+            let figlio = match figli.next() {
+                Some(figlio) => {
+                    aperti -= 1;
+                    figlio
+                }
+                None => {
+                    pila.pop();
+                    cammino.remove(&self.albero[id].stato);
+                    if !pila.is_empty() {
+                        self.albero.truncate(id);
+                    }
+                    continue;
+                }
+            };
+            if cammino.contains(&figlio.stato) {
+                self.statistiche.duplicati += 1;
+                continue;
+            }
+            if let Some(generati) = generati.as_mut() {
+                if ripetuto(generati, &figlio, &self.albero[id].stato) {
+                    self.statistiche.duplicati += 1;
+                    continue;
+                }
+            }
+            let f = figlio
+                .costo_cammino
+                .saturating_add(self.euristica.h(&figlio.stato));
+            if f > soglia {
+                superata = Some(superata.map_or(f, |s| s.min(f)));
+                continue;
+            }
+            let figlio = self.albero.aggiungi(figlio);
+            if self.problema.goal_test(&self.albero[figlio].stato) {
+                return Ok(self.albero.soluzione(figlio));
+            }
+            if self.esaurito() {
+                return Err(None);
+            }
+            cammino.insert(self.albero[figlio].stato.clone());
+            self.conta_riespansione(precedente.is_some_and(|p| f <= p));
+            let figli = self.espandi(figlio);
+            aperti += figli.len();
+            pila.push((figlio, figli));
+            self.misura(aperti, generati.as_ref().map(HashMap::len));
+        }
+        Err(superata)
+    }
+    fn conta_riespansione(&mut self, ripetuta: bool) {
+        if ripetuta {
+            self.statistiche.riespansioni += 1;
+        }
+    }
+    /// Recursive best-first search: a depth-first search that only goes on while the f of the
+    /// current path is not worse than the best alternative, keeping for every forgotten subtree
+    /// the lowest f of its frontier. It runs on an explicit stack and, like `ida_star_search`,
+    /// skips the states on the current path
+    pub fn recursive_best_first_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(Self::best_first_ricorsiva)
+    }
+    fn best_first_ricorsiva(&mut self) -> SearchResult<P::State> {
+        let grafo = self.in_grafo(Modalita::Tree);
+        self.inizia(grafo);
+        if self.problema.ha_costi_negativi() {
+            return SearchResult::NegativeCost;
+        }
+        let radice = self.albero.radice(self.problema.stato_iniziale());
+        if self.problema.goal_test(&self.albero[radice].stato) {
+            return SearchResult::Found(self.albero.soluzione(radice));
+        }
+        let mut cammino = HashSet::new();
+        cammino.insert(self.problema.stato_iniziale());
+        let mut generati = if grafo { Some(HashMap::new()) } else { None };
+        let f = self.euristica.h(&self.problema.stato_iniziale());
+        let figli = self.successori_rbfs(radice, f, &cammino, generati.as_mut(), None);
+        // the children of the nodes on the path, kept to back up their f
+        let mut aperti = figli.len();
+        let mut pila = vec![Chiamata {
+            id: radice,
+            limite: i32::MAX,
+            figli,
+            gia_esplorato: None,
+        }];
+        // the backed-up f of the subtree that was just left, for the caller
+        let mut ritorno: Option<i32> = None;
+        while let Some(chiamata) = pila.last_mut() {
+            if let Some(f) = ritorno.take() {
+                let (migliore, _) = migliori(&chiamata.figli);
+                chiamata.figli[migliore].f = f;
+            }
+            if chiamata.figli.is_empty() {
+                ritorno = Some(i32::MAX);
+            } else {
+                let (migliore, alternativa) = migliori(&chiamata.figli);
+                let f = chiamata.figli[migliore].f;
+                if f <= chiamata.limite && f != i32::MAX {
+                    let limite = chiamata.limite.min(alternativa);
+                    let successore = &mut chiamata.figli[migliore];
+                    // a subtree left before is searched again up to its backed-up f
+                    let gia_esplorato = if successore.esplorato {
+                        self.statistiche.iterazioni += 1;
+                        Some(successore.f).max(chiamata.gia_esplorato)
+                    } else {
+                        chiamata.gia_esplorato
+                    };
+                    successore.esplorato = true;
+                    let figlio = self.albero.aggiungi(successore.nodo.clone());
+                    if self.problema.goal_test(&self.albero[figlio].stato) {
+                        return SearchResult::Found(self.albero.soluzione(figlio));
+                    }
+                    if self.esaurito() {
+                        return SearchResult::BudgetExceeded;
+                    }
+                    cammino.insert(self.albero[figlio].stato.clone());
+                    let figli =
+                        self.successori_rbfs(figlio, f, &cammino, generati.as_mut(), gia_esplorato);
+                    aperti += figli.len();
+                    pila.push(Chiamata {
+                        id: figlio,
+                        limite,
+                        figli,
+                        gia_esplorato,
+                    });
+                    self.misura(aperti, generati.as_ref().map(HashMap::len));
+                    continue;
+                }
+                ritorno = Some(f);
+            }
+            // the subtree is forgotten, its best f is backed up to the caller
+            let chiamata = pila.pop().unwrap();
+            aperti -= chiamata.figli.len();
+            cammino.remove(&self.albero[chiamata.id].stato);
+            if !pila.is_empty() {
+                self.albero.truncate(chiamata.id);
+            }
+        }
+        SearchResult::Failure
+    }
+    /// Expands the node `id`, whose f is `f`: the f of a child is never lower than the one of
+    /// its parent, that may have been backed up from a forgotten subtree. The children already
+    /// `generati` are skipped in graph mode
+    fn successori_rbfs(
+        &mut self,
+        id: NodeId,
+        f: i32,
+        cammino: &HashSet<P::State>,
+        mut generati: Option<&mut Generati<P::State>>,
+        gia_esplorato: Option<i32>,
+    ) -> Vec<Successore<P::State>> {
+        // the nodes of a forgotten subtree were expanded when their own f was below its backed-up f
+        let nodo = &self.albero[id];
+        let proprio = nodo
+            .costo_cammino
+            .saturating_add(self.euristica.h(&nodo.stato));
+        self.conta_riespansione(gia_esplorato.is_some_and(|g| proprio < g));
+        let stato = self.albero[id].stato.clone();
+        let figli = self.espandi(id);
+        let generati_tutti = figli.len();
+        let figli: Vec<Node<P::State>> = figli
+            .filter(|figlio| !cammino.contains(&figlio.stato))
+            .filter(|figlio| match generati.as_mut() {
+                Some(generati) => !ripetuto(generati, figlio, &stato),
+                None => true,
+            })
+            .collect();
+        self.statistiche.duplicati += generati_tutti - figli.len();
+        figli
+            .into_iter()
+            .map(|nodo| Successore {
+                f: nodo
+                    .costo_cammino
+                    .saturating_add(self.euristica.h(&nodo.stato))
+                    .max(f),
+                nodo,
+                esplorato: false,
+            })
+            .collect()
+    }
+    /// Bellman-Ford with a FIFO queue of improved states (SPFA): negative costs are allowed,
+    /// negative cycles reachable from the initial state are reported. As a tree search it is
+    /// not applicable
+    pub fn bellman_ford_search(&mut self) -> (SearchResult<P::State>, SearchStats) {
+        self.con_statistiche(Self::bellman_ford)
+    }
+    fn bellman_ford(&mut self) -> SearchResult<P::State> {
+        self.inizia(true);
+        if !self.in_grafo(Modalita::Graph) {
+            return SearchResult::Unsupported;
+        }
+        let radice = self.albero.radice(self.problema.stato_iniziale());
+
+        let mut frontiera = VecDeque::new();
+        frontiera.push_back(self.problema.stato_iniziale());
+        let mut in_frontiera = HashSet::new();
+        in_frontiera.insert(self.problema.stato_iniziale());
+
+        // every improvement adds a new node, raggiunti holds the cheapest one of each state
+        let mut raggiunti = HashMap::new();
+        raggiunti.insert(self.problema.stato_iniziale(), radice);
+
+        while let Some(s) = frontiera.pop_front() {
+            in_frontiera.remove(&s);
+            for figlio in self.espandi(raggiunti[&s]) {
+                if !raggiunti.contains_key(&figlio.stato)
+                    || self.albero[raggiunti[&figlio.stato]].costo_cammino > figlio.costo_cammino
+                {
+                    let s = figlio.stato.clone();
+                    let figlio = self.albero.aggiungi(figlio);
+                    raggiunti.insert(s.clone(), figlio);
+                    // a path longer than the number of reached states repeats a state
+                    if self.albero[figlio].profondita >= raggiunti.len() {
+                        return SearchResult::NegativeCycle(self.ciclo(figlio));
+                    }
+                    if in_frontiera.insert(s.clone()) {
+                        frontiera.push_back(s);
+                    }
+                } else {
+                    self.statistiche.duplicati += 1;
+                }
+            }
+            self.misura(frontiera.len(), Some(raggiunti.len()));
+        }
+        // without a single goal state, the cheapest reached goal is returned
+        raggiunti
+            .values()
+            .filter(|&&id| self.problema.goal_test(&self.albero[id].stato))
+            .min_by_key(|&&id| self.albero[id].costo_cammino)
+            .map_or(SearchResult::Failure, |&id| {
+                SearchResult::Found(self.albero.soluzione(id))
+            })
+    }
+    /// First cycle met walking from the node `id` towards the root; every repeated state was
+    /// reached again with a lower cost, so the cycle is negative
+    fn ciclo(&self, id: NodeId) -> Vec<P::State> {
+        let mut visti = HashMap::new();
+        let mut stati = Vec::new();
+        let mut corrente = Some(id);
+        while let Some(id) = corrente {
+            let s = &self.albero[id].stato;
+            if let Some(&inizio) = visti.get(s) {
+                let mut ciclo: Vec<P::State> = stati[inizio..].to_vec();
+                ciclo.push(s.clone());
+                ciclo.reverse();
+                return ciclo;
+            }
+            visti.insert(s, stati.len());
+            stati.push(s.clone());
+            corrente = self.albero[id].genitore;
+        }
+        Vec::new()
+    }
+    /// Joins the forward node `avanti` with the backward node `indietro`, both holding the same state
+    fn unisci(&self, avanti: NodeId, indietro: NodeId) -> Solution<P::State> {
+        // forward half up to the common state, then the backward half walked towards its root
+        let mut soluzione = self.albero.soluzione(avanti);
+        let mut corrente = indietro;
+        while let Some(genitore) = self.albero[corrente].genitore {
+            soluzione.push(
+                self.albero[genitore].stato.clone(),
+                self.albero[corrente]
+                    .costo_cammino
+                    .saturating_sub(self.albero[genitore].costo_cammino),
+            );
+            corrente = genitore;
+        }
+        soluzione
+    }
+    /// Children of the node `id`; they reference it as parent but are not yet part of the tree
+    fn espandi(&mut self, id: NodeId) -> impl ExactSizeIterator<Item = Node<P::State>> {
+        self.espandi_verso(id, true)
+    }
+    /// Like `espandi`, but follows the actions backwards when `avanti` is false; the path costs
+    /// saturate at the bounds of `i32` instead of overflowing
+    fn espandi_verso(
+        &mut self,
+        id: NodeId,
+        avanti: bool,
+    ) -> impl ExactSizeIterator<Item = Node<P::State>> {
+        self.albero.segna_espanso(id);
+        let nodo = &self.albero[id];
+        let figlio = |azione: Action<P::State>| Node {
+            stato: azione.risultato,
+            genitore: Some(id),
+            costo_cammino: nodo.costo_cammino.saturating_add(azione.costo),
+            profondita: nodo.profondita + 1,
+        };
+        // collected once, as the callers add the children to the search tree while they go
+        // through them
+        let yield_nodo: Vec<Node<P::State>> = if avanti {
+            self.problema.successori(&nodo.stato).map(figlio).collect()
+        } else {
+            // available, as checked by `bi_directional_search`
+            self.problema
+                .predecessori(&nodo.stato)
+                .into_iter()
+                .flatten()
+                .map(figlio)
+                .collect()
+        };
+        self.statistiche.espansi += 1;
+        self.statistiche.generati += yield_nodo.len();
+        yield_nodo.into_iter()
+    }
+}
@@ -0,0 +1,62 @@
+use std::fmt;
+
+use problem::node::*;
+
+/// Path found by a search: `stati` goes from the initial to the final state,
+/// `costi[i]` is the cost of the action from `stati[i]` to `stati[i + 1]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<S = State> {
+    pub stati: Vec<S>,
+    pub costi: Vec<i32>,
+}
+
+impl<S> Default for Solution<S> {
+    fn default() -> Self {
+        Solution {
+            stati: Vec::new(),
+            costi: Vec::new(),
+        }
+    }
+}
+
+impl<S> Solution<S> {
+    pub fn profondita(&self) -> usize {
+        self.costi.len()
+    }
+    /// Total cost of the path, saturating at the bounds of `i32` like the path costs of a search
+    pub fn costo(&self) -> i32 {
+        self.costi
+            .iter()
+            .fold(0, |totale, &c| totale.saturating_add(c))
+    }
+    /// Appends the action leading from the last state of the path to `stato`
+    pub fn push(&mut self, stato: S, costo: i32) {
+        self.stati.push(stato);
+        self.costi.push(costo);
+    }
+    /// The same path with every state replaced by `f(stato)`
+    pub fn map<T, F: FnMut(&S) -> T>(&self, f: F) -> Solution<T> {
+        Solution {
+            stati: self.stati.iter().map(f).collect(),
+            costi: self.costi.clone(),
+        }
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Solution<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // costs are only shown when the graph is weighted
+        let pesato = self.costi.iter().any(|&c| c != 0);
+        for (i, stato) in self.stati.iter().enumerate() {
+            if i > 0 {
+                if pesato {
+                    write!(f, " -({})-> ", self.costi[i - 1])?;
+                } else {
+                    write!(f, " -> ")?;
+                }
+            }
+            write!(f, "{}", stato)?;
+        }
+        Ok(())
+    }
+}
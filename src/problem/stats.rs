@@ -0,0 +1,336 @@
+//! Summary of the structure of a loaded graph, printed by `eia stats`.
+use std::cmp;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use problem::graph::*;
+use problem::node::*;
+
+/// Sweeps of the diameter estimate, each one a breadth-first visit of the largest component
+const SWEEP: usize = 4;
+
+/// How many states have each value, ordered by value
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Distribuzione {
+    pub conteggi: BTreeMap<usize, usize>,
+}
+
+impl Distribuzione {
+    fn new(valori: impl Iterator<Item = usize>) -> Self {
+        let mut conteggi = BTreeMap::new();
+        for valore in valori {
+            *conteggi.entry(valore).or_insert(0) += 1;
+        }
+        Distribuzione { conteggi }
+    }
+    pub fn min(&self) -> usize {
+        self.conteggi.keys().next().cloned().unwrap_or(0)
+    }
+    pub fn max(&self) -> usize {
+        self.conteggi.keys().next_back().cloned().unwrap_or(0)
+    }
+    pub fn media(&self) -> f64 {
+        let totale: usize = self.conteggi.values().sum();
+        if totale == 0 {
+            return 0.0;
+        }
+        let somma: usize = self.conteggi.iter().map(|(v, c)| v * c).sum();
+        somma as f64 / totale as f64
+    }
+    /// The lower median
+    pub fn mediana(&self) -> usize {
+        let totale: usize = self.conteggi.values().sum();
+        let mut visti = 0;
+        for (&valore, &conteggio) in &self.conteggi {
+            visti += conteggio;
+            if 2 * visti >= totale {
+                return valore;
+            }
+        }
+        0
+    }
+    fn to_json(&self) -> Value {
+        let conteggi: Map<String, Value> = self
+            .conteggi
+            .iter()
+            .map(|(v, c)| (v.to_string(), Value::from(*c)))
+            .collect();
+        let mut json = Map::new();
+        json.insert("min".into(), self.min().into());
+        json.insert("max".into(), self.max().into());
+        json.insert("mean".into(), self.media().into());
+        json.insert("median".into(), self.mediana().into());
+        json.insert("counts".into(), Value::Object(conteggi));
+        Value::Object(json)
+    }
+}
+
+/// Number and sizes of the components of a graph; `dimensioni` counts the components of each size
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Componenti {
+    pub numero: usize,
+    pub piu_grande: usize,
+    pub dimensioni: Distribuzione,
+}
+
+impl Componenti {
+    fn new(dimensioni: Vec<usize>) -> Self {
+        Componenti {
+            numero: dimensioni.len(),
+            piu_grande: dimensioni.iter().cloned().max().unwrap_or(0),
+            dimensioni: Distribuzione::new(dimensioni.into_iter()),
+        }
+    }
+    fn to_json(&self) -> Value {
+        let mut json = Map::new();
+        json.insert("count".into(), self.numero.into());
+        json.insert("largest".into(), self.piu_grande.into());
+        json.insert("sizes".into(), self.dimensioni.to_json());
+        Value::Object(json)
+    }
+}
+
+/// Counts, degrees and connectivity of a graph. The degrees count the actions, so an
+/// undirected edge adds 1 to the out and in degree of both its states.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphStats {
+    pub tipo: GraphType,
+    pub pesato: bool,
+    pub nodi: usize,
+    pub archi: usize,
+    pub isolati: usize,
+    pub cappi: usize,
+    pub grado_uscente: Distribuzione,
+    pub grado_entrante: Distribuzione,
+    pub componenti_deboli: Componenti,
+    /// The same as the weak ones for undirected graphs
+    pub componenti_forti: Componenti,
+    /// Lower bound of the diameter in actions of the largest weak component, ignoring the
+    /// direction of the edges: the largest eccentricity met by repeated double sweeps
+    pub diametro: usize,
+}
+
+impl GraphStats {
+    pub fn new(grafo: &Graph) -> Self {
+        let n = grafo.num_nodi() as State;
+        let (componente, deboli) = componenti_deboli(grafo);
+        let forti = if grafo.is_directed() {
+            componenti_forti(grafo)
+        } else {
+            deboli.clone()
+        };
+        GraphStats {
+            tipo: grafo.gtype(),
+            pesato: grafo.is_weighted(),
+            nodi: grafo.num_nodi(),
+            archi: grafo.num_archi(),
+            isolati: (0..n).filter(|&s| grafo.is_isolated(s)).count(),
+            cappi: (0..n)
+                .filter(|&s| grafo.vicini(s).binary_search(&s).is_ok())
+                .count(),
+            grado_uscente: Distribuzione::new((0..n).map(|s| grafo.vicini(s).len())),
+            grado_entrante: Distribuzione::new((0..n).map(|s| grafo.azioni_inverse(s).count())),
+            diametro: diametro(grafo, &componente, &deboli),
+            componenti_deboli: Componenti::new(deboli),
+            componenti_forti: Componenti::new(forti),
+        }
+    }
+    pub fn to_json(&self) -> Value {
+        let mut json = Map::new();
+        json.insert("directed".into(), (self.tipo == GraphType::Directed).into());
+        json.insert("weighted".into(), self.pesato.into());
+        json.insert("nodes".into(), self.nodi.into());
+        json.insert("edges".into(), self.archi.into());
+        json.insert("isolated".into(), self.isolati.into());
+        json.insert("self_loops".into(), self.cappi.into());
+        json.insert("out_degree".into(), self.grado_uscente.to_json());
+        json.insert("in_degree".into(), self.grado_entrante.to_json());
+        json.insert(
+            "weakly_connected_components".into(),
+            self.componenti_deboli.to_json(),
+        );
+        json.insert(
+            "strongly_connected_components".into(),
+            self.componenti_forti.to_json(),
+        );
+        json.insert("diameter_lower_bound".into(), self.diametro.into());
+        Value::Object(json)
+    }
+}
+
+impl fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gradi = |d: &Distribuzione| {
+            format!(
+                "{} / {} / {:.2} / {}",
+                d.min(),
+                d.mediana(),
+                d.media(),
+                d.max()
+            )
+        };
+        let componenti =
+            |c: &Componenti| format!("{} (la più grande: {} nodi)", c.numero, c.piu_grande);
+        writeln!(f, "{:<40}{}", "Tipo", self.tipo)?;
+        writeln!(
+            f,
+            "{:<40}{}",
+            "Pesato",
+            if self.pesato { "sì" } else { "no" }
+        )?;
+        writeln!(f, "{:<40}{}", "Nodi", self.nodi)?;
+        writeln!(f, "{:<40}{}", "Archi", self.archi)?;
+        writeln!(f, "{:<40}{}", "Nodi isolati", self.isolati)?;
+        writeln!(f, "{:<40}{}", "Cappi", self.cappi)?;
+        writeln!(
+            f,
+            "{:<40}{}",
+            "Grado uscente (min/mediana/media/max)",
+            gradi(&self.grado_uscente)
+        )?;
+        writeln!(
+            f,
+            "{:<40}{}",
+            "Grado entrante (min/mediana/media/max)",
+            gradi(&self.grado_entrante)
+        )?;
+        writeln!(
+            f,
+            "{:<40}{}",
+            "Componenti debolmente connesse",
+            componenti(&self.componenti_deboli)
+        )?;
+        writeln!(
+            f,
+            "{:<40}{}",
+            "Componenti fortemente connesse",
+            componenti(&self.componenti_forti)
+        )?;
+        write!(f, "{:<40}≥ {}", "Diametro stimato", self.diametro)
+    }
+}
+
+/// The weak component of every state, and the size of every component
+fn componenti_deboli(grafo: &Graph) -> (Vec<usize>, Vec<usize>) {
+    let n = grafo.num_nodi();
+    let mut componente = vec![usize::MAX; n];
+    let mut dimensioni = Vec::new();
+    let mut coda = VecDeque::new();
+    for radice in 0..n {
+        if componente[radice] != usize::MAX {
+            continue;
+        }
+        let indice = dimensioni.len();
+        componente[radice] = indice;
+        coda.push_back(radice as State);
+        let mut dimensione = 0;
+        while let Some(s) = coda.pop_front() {
+            dimensione += 1;
+            for azione in grafo.azioni(s).chain(grafo.azioni_inverse(s)) {
+                let t = azione.risultato as usize;
+                if componente[t] == usize::MAX {
+                    componente[t] = indice;
+                    coda.push_back(azione.risultato);
+                }
+            }
+        }
+        dimensioni.push(dimensione);
+    }
+    (componente, dimensioni)
+}
+
+/// Sizes of the strong components, with Tarjan's algorithm on an explicit stack
+fn componenti_forti(grafo: &Graph) -> Vec<usize> {
+    let n = grafo.num_nodi();
+    let mut indice = vec![usize::MAX; n];
+    let mut minimo = vec![0; n];
+    let mut in_pila = vec![false; n];
+    let mut pila = Vec::new();
+    // the states being visited, with the position of the next action to follow
+    let mut chiamate: Vec<(usize, usize)> = Vec::new();
+    let mut prossimo = 0;
+    let mut dimensioni = Vec::new();
+    for radice in 0..n {
+        if indice[radice] != usize::MAX {
+            continue;
+        }
+        indice[radice] = prossimo;
+        minimo[radice] = prossimo;
+        prossimo += 1;
+        pila.push(radice);
+        in_pila[radice] = true;
+        chiamate.push((radice, 0));
+        while let Some(&mut (s, ref mut azione)) = chiamate.last_mut() {
+            let vicini = grafo.vicini(s as State);
+            if *azione < vicini.len() {
+                let t = vicini[*azione] as usize;
+                *azione += 1;
+                if indice[t] == usize::MAX {
+                    indice[t] = prossimo;
+                    minimo[t] = prossimo;
+                    prossimo += 1;
+                    pila.push(t);
+                    in_pila[t] = true;
+                    chiamate.push((t, 0));
+                } else if in_pila[t] {
+                    minimo[s] = cmp::min(minimo[s], indice[t]);
+                }
+                continue;
+            }
+            chiamate.pop();
+            if let Some(&(genitore, _)) = chiamate.last() {
+                minimo[genitore] = cmp::min(minimo[genitore], minimo[s]);
+            }
+            // s is the root of a component, made of the states above it in the stack
+            if minimo[s] == indice[s] {
+                let mut dimensione = 0;
+                while let Some(t) = pila.pop() {
+                    in_pila[t] = false;
+                    dimensione += 1;
+                    if t == s {
+                        break;
+                    }
+                }
+                dimensioni.push(dimensione);
+            }
+        }
+    }
+    dimensioni
+}
+
+/// Double sweeps from a state of the largest weak component: every visit starts from the
+/// farthest state met by the previous one
+fn diametro(grafo: &Graph, componente: &[usize], dimensioni: &[usize]) -> usize {
+    let piu_grande = match (0..dimensioni.len()).max_by_key(|&c| dimensioni[c]) {
+        Some(c) => c,
+        None => return 0,
+    };
+    let mut inizio = componente.iter().position(|&c| c == piu_grande).unwrap() as State;
+    let mut distanza = vec![usize::MAX; grafo.num_nodi()];
+    let mut coda = VecDeque::new();
+    let mut stima = 0;
+    for _ in 0..SWEEP {
+        for d in distanza.iter_mut() {
+            *d = usize::MAX;
+        }
+        distanza[inizio as usize] = 0;
+        coda.push_back(inizio);
+        let mut lontano = inizio;
+        while let Some(s) = coda.pop_front() {
+            lontano = s;
+            for azione in grafo.azioni(s).chain(grafo.azioni_inverse(s)) {
+                let t = azione.risultato as usize;
+                if distanza[t] == usize::MAX {
+                    distanza[t] = distanza[s as usize] + 1;
+                    coda.push_back(azione.risultato);
+                }
+            }
+        }
+        // the last state dequeued is one of the farthest
+        stima = cmp::max(stima, distanza[lontano as usize]);
+        inizio = lontano;
+    }
+    stima
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+use std::fs;
+
+use error::*;
+use problem::heuristic::*;
+use problem::node::*;
+use problem::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub riga: usize,
+    pub colonna: usize,
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{})", self.riga, self.colonna)
+    }
+}
+
+/// Grid maze: `#` is a wall, `S` the start, `G` the goal and any other character a free cell.
+/// Moves go to the 4 adjacent cells and cost 1.
+pub struct GridMaze {
+    muri: Vec<Vec<bool>>,
+    inizio: Cell,
+    fine: Cell,
+}
+
+impl GridMaze {
+    pub fn from_file(path: &str) -> Result<Self, Error> {
+        let testo = fs::read_to_string(path).map_err(|errore| Error::File {
+            path: path.to_string(),
+            errore,
+        })?;
+        GridMaze::parse(&testo)
+    }
+    pub fn parse(testo: &str) -> Result<Self, Error> {
+        let mut muri = Vec::new();
+        let mut inizio = None;
+        let mut fine = None;
+        for (riga, linea) in testo.lines().enumerate() {
+            let mut muri_riga = Vec::new();
+            for (colonna, c) in linea.chars().enumerate() {
+                match c {
+                    'S' => inizio = Some(Cell { riga, colonna }),
+                    'G' => fine = Some(Cell { riga, colonna }),
+                    _ => {}
+                }
+                muri_riga.push(c == '#');
+            }
+            muri.push(muri_riga);
+        }
+        let manca = |cella: &str| Error::InvalidProblem(format!("Il labirinto non ha {}", cella));
+        Ok(GridMaze {
+            muri,
+            inizio: inizio.ok_or_else(|| manca("un inizio (S)"))?,
+            fine: fine.ok_or_else(|| manca("una fine (G)"))?,
+        })
+    }
+    /// Cells outside the shorter rows are walls
+    fn libera(&self, riga: usize, colonna: usize) -> bool {
+        self.muri
+            .get(riga)
+            .and_then(|r| r.get(colonna))
+            .is_some_and(|&muro| !muro)
+    }
+}
+
+impl SearchProblem for GridMaze {
+    type State = Cell;
+
+    fn stato_iniziale(&self) -> Cell {
+        self.inizio
+    }
+    fn goal_test(&self, stato: &Cell) -> bool {
+        *stato == self.fine
+    }
+    fn successori(&self, stato: &Cell) -> impl Iterator<Item = Action<Cell>> {
+        let (r, c) = (stato.riga, stato.colonna);
+        let mut vicine = vec![(r + 1, c), (r, c + 1)];
+        if r > 0 {
+            vicine.push((r - 1, c));
+        }
+        if c > 0 {
+            vicine.push((r, c - 1));
+        }
+        vicine
+            .into_iter()
+            .filter(move |&(r, c)| self.libera(r, c))
+            .map(|(riga, colonna)| Action {
+                risultato: Cell { riga, colonna },
+                costo: 1,
+            })
+    }
+    /// Moves between free cells can be undone
+    fn predecessori(&self, stato: &Cell) -> Option<impl Iterator<Item = Action<Cell>>> {
+        Some(self.successori(stato))
+    }
+    fn stato_finale(&self) -> Option<Cell> {
+        Some(self.fine)
+    }
+}
+
+/// Manhattan distance from the goal cell
+pub struct ManhattanCells {
+    pub fine: Cell,
+}
+
+impl Heuristic<Cell> for ManhattanCells {
+    fn h(&self, state: &Cell) -> i32 {
+        (state.riga.abs_diff(self.fine.riga) + state.colonna.abs_diff(self.fine.colonna)) as i32
+    }
+}
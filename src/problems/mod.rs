@@ -0,0 +1,21 @@
+pub mod maze;
+pub mod puzzle;
+pub mod queens;
+pub mod romania;
+
+use clap::ValueEnum;
+
+/// Problems selectable from the command line
+#[derive(Debug, Clone, ValueEnum, PartialEq)]
+pub enum Dominio {
+    /// Cammino tra due nodi del dataset
+    Graph,
+    /// 8-puzzle o 15-puzzle
+    Puzzle,
+    /// N regine
+    Queens,
+    /// Labirinto letto da un file ASCII
+    Maze,
+    /// Mappa della Romania
+    Romania,
+}
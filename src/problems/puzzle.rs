@@ -0,0 +1,158 @@
+use std::fmt;
+
+use error::*;
+use problem::heuristic::*;
+use problem::node::*;
+use problem::*;
+
+/// Tiles of a sliding puzzle read row by row, 0 is the blank
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tiles(pub Vec<u8>);
+
+impl fmt::Display for Tiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tessere: Vec<String> = self.0.iter().map(|t| t.to_string()).collect();
+        write!(f, "[{}]", tessere.join(","))
+    }
+}
+
+/// 8-puzzle (3x3) or 15-puzzle (4x4); the goal has the blank first and the tiles in order
+pub struct SlidingPuzzle {
+    iniziale: Tiles,
+    lato: usize,
+}
+
+impl SlidingPuzzle {
+    pub fn new(iniziale: Vec<u8>) -> Result<Self, Error> {
+        let lato = match iniziale.len() {
+            9 => 3,
+            16 => 4,
+            _ => {
+                return Err(Error::InvalidProblem(
+                    "Il puzzle deve avere 9 o 16 tessere".to_string(),
+                ))
+            }
+        };
+        let mut ordinate = iniziale.clone();
+        ordinate.sort();
+        if ordinate != (0..iniziale.len() as u8).collect::<Vec<u8>>() {
+            return Err(Error::InvalidProblem(format!(
+                "Le tessere devono essere i numeri da 0 a {}",
+                iniziale.len() - 1
+            )));
+        }
+        let puzzle = SlidingPuzzle {
+            iniziale: Tiles(iniziale),
+            lato,
+        };
+        if !puzzle.risolvibile() {
+            return Err(Error::InvalidProblem(
+                "Il puzzle non è risolvibile".to_string(),
+            ));
+        }
+        Ok(puzzle)
+    }
+    fn finale(&self) -> Tiles {
+        Tiles((0..(self.lato * self.lato) as u8).collect())
+    }
+    /// Half of the permutations can't reach the goal: the parity of the inversions (plus the
+    /// row of the blank on even sides) is invariant under the moves
+    fn risolvibile(&self) -> bool {
+        let tessere: Vec<u8> = self
+            .iniziale
+            .0
+            .iter()
+            .cloned()
+            .filter(|&t| t != 0)
+            .collect();
+        let mut inversioni = 0;
+        for i in 0..tessere.len() {
+            for j in i + 1..tessere.len() {
+                if tessere[i] > tessere[j] {
+                    inversioni += 1;
+                }
+            }
+        }
+        if self.lato.is_multiple_of(2) {
+            let vuoto = self.iniziale.0.iter().position(|&t| t == 0).unwrap();
+            inversioni += vuoto / self.lato;
+        }
+        inversioni % 2 == 0
+    }
+}
+
+impl SearchProblem for SlidingPuzzle {
+    type State = Tiles;
+
+    fn stato_iniziale(&self) -> Tiles {
+        self.iniziale.clone()
+    }
+    fn goal_test(&self, stato: &Tiles) -> bool {
+        stato.0.iter().enumerate().all(|(i, &t)| i == t as usize)
+    }
+    /// The blank moves up, down, left or right, every move costs 1
+    fn successori(&self, stato: &Tiles) -> impl Iterator<Item = Action<Tiles>> {
+        let vuoto = stato.0.iter().position(|&t| t == 0).unwrap();
+        let (riga, colonna) = (vuoto / self.lato, vuoto % self.lato);
+        let mut azioni = Vec::with_capacity(4);
+        let mut muovi = |destinazione: usize| {
+            let mut tessere = stato.0.clone();
+            tessere.swap(vuoto, destinazione);
+            azioni.push(Action {
+                risultato: Tiles(tessere),
+                costo: 1,
+            });
+        };
+        if riga > 0 {
+            muovi(vuoto - self.lato);
+        }
+        if riga + 1 < self.lato {
+            muovi(vuoto + self.lato);
+        }
+        if colonna > 0 {
+            muovi(vuoto - 1);
+        }
+        if colonna + 1 < self.lato {
+            muovi(vuoto + 1);
+        }
+        azioni.into_iter()
+    }
+    /// Every move of the blank can be undone
+    fn predecessori(&self, stato: &Tiles) -> Option<impl Iterator<Item = Action<Tiles>>> {
+        Some(self.successori(stato))
+    }
+    fn stato_finale(&self) -> Option<Tiles> {
+        Some(self.finale())
+    }
+}
+
+/// Number of tiles out of place, the blank excluded
+pub struct MisplacedTiles;
+
+impl Heuristic<Tiles> for MisplacedTiles {
+    fn h(&self, state: &Tiles) -> i32 {
+        state
+            .0
+            .iter()
+            .enumerate()
+            .filter(|&(i, &t)| t != 0 && i != t as usize)
+            .count() as i32
+    }
+}
+
+/// Sum of the distances of every tile from its goal position, the blank excluded
+pub struct ManhattanTiles;
+
+impl Heuristic<Tiles> for ManhattanTiles {
+    fn h(&self, state: &Tiles) -> i32 {
+        let lato = (state.0.len() as f64).sqrt() as usize;
+        let mut h = 0;
+        for (i, &t) in state.0.iter().enumerate() {
+            if t != 0 {
+                let t = t as usize;
+                h += (i / lato).abs_diff(t / lato) + (i % lato).abs_diff(t % lato);
+            }
+        }
+        h as i32
+    }
+}
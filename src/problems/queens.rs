@@ -0,0 +1,67 @@
+use std::fmt;
+
+use problem::node::*;
+use problem::*;
+
+/// Columns of the queens placed so far, one per row starting from the first
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Queens(pub Vec<usize>);
+
+impl fmt::Display for Queens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let colonne: Vec<String> = self.0.iter().map(|c| c.to_string()).collect();
+        write!(f, "[{}]", colonne.join(","))
+    }
+}
+
+/// Incremental formulation of the N-queens problem: every action places a queen in the first
+/// free row, on a square not attacked by the queens already placed
+pub struct NQueens {
+    n: usize,
+}
+
+impl NQueens {
+    pub fn new(n: usize) -> Self {
+        NQueens { n }
+    }
+}
+
+impl SearchProblem for NQueens {
+    type State = Queens;
+
+    fn stato_iniziale(&self) -> Queens {
+        Queens(Vec::new())
+    }
+    fn goal_test(&self, stato: &Queens) -> bool {
+        stato.0.len() == self.n
+    }
+    fn successori(&self, stato: &Queens) -> impl Iterator<Item = Action<Queens>> {
+        // with every row filled, every column is taken
+        let riga = stato.0.len();
+        (0..self.n)
+            .filter(move |&colonna| {
+                stato
+                    .0
+                    .iter()
+                    .enumerate()
+                    .all(|(r, &c)| c != colonna && riga - r != c.abs_diff(colonna))
+            })
+            .map(move |colonna| {
+                let mut regine = stato.0.clone();
+                regine.push(colonna);
+                Action {
+                    risultato: Queens(regine),
+                    costo: 1,
+                }
+            })
+    }
+    /// Removing the last queen
+    fn predecessori(&self, stato: &Queens) -> Option<impl Iterator<Item = Action<Queens>>> {
+        let mut regine = stato.0.clone();
+        let azione = regine.pop().map(|_| Action {
+            risultato: Queens(regine),
+            costo: 1,
+        });
+        Some(azione.into_iter())
+    }
+}
@@ -0,0 +1,135 @@
+use error::*;
+use problem::heuristic::*;
+use problem::node::*;
+use problem::*;
+
+pub type City = &'static str;
+
+/// Road distances in km between the cities of the map in AIMA, figure 3.1
+const STRADE: [(City, City, i32); 23] = [
+    ("Arad", "Zerind", 75),
+    ("Arad", "Sibiu", 140),
+    ("Arad", "Timisoara", 118),
+    ("Bucharest", "Urziceni", 85),
+    ("Bucharest", "Pitesti", 101),
+    ("Bucharest", "Giurgiu", 90),
+    ("Bucharest", "Fagaras", 211),
+    ("Craiova", "Drobeta", 120),
+    ("Craiova", "RimnicuVilcea", 146),
+    ("Craiova", "Pitesti", 138),
+    ("Drobeta", "Mehadia", 75),
+    ("Eforie", "Hirsova", 86),
+    ("Fagaras", "Sibiu", 99),
+    ("Hirsova", "Urziceni", 98),
+    ("Iasi", "Vaslui", 92),
+    ("Iasi", "Neamt", 87),
+    ("Lugoj", "Timisoara", 111),
+    ("Lugoj", "Mehadia", 70),
+    ("Oradea", "Zerind", 71),
+    ("Oradea", "Sibiu", 151),
+    ("Pitesti", "RimnicuVilcea", 97),
+    ("RimnicuVilcea", "Sibiu", 80),
+    ("Urziceni", "Vaslui", 142),
+];
+
+/// Straight-line distances in km to Bucharest, AIMA figure 3.16
+const DISTANZE_BUCHAREST: [(City, i32); 20] = [
+    ("Arad", 366),
+    ("Bucharest", 0),
+    ("Craiova", 160),
+    ("Drobeta", 242),
+    ("Eforie", 161),
+    ("Fagaras", 176),
+    ("Giurgiu", 77),
+    ("Hirsova", 151),
+    ("Iasi", 226),
+    ("Lugoj", 244),
+    ("Mehadia", 241),
+    ("Neamt", 234),
+    ("Oradea", 380),
+    ("Pitesti", 100),
+    ("RimnicuVilcea", 193),
+    ("Sibiu", 253),
+    ("Timisoara", 329),
+    ("Urziceni", 80),
+    ("Vaslui", 199),
+    ("Zerind", 374),
+];
+
+/// Route finding between two cities of the map of Romania
+pub struct RomaniaMap {
+    partenza: City,
+    arrivo: City,
+}
+
+impl RomaniaMap {
+    pub fn new(partenza: &str, arrivo: &str) -> Result<Self, Error> {
+        Ok(RomaniaMap {
+            partenza: RomaniaMap::citta(partenza)?,
+            arrivo: RomaniaMap::citta(arrivo)?,
+        })
+    }
+    /// The city of the map with the given name, ignoring case
+    fn citta(nome: &str) -> Result<City, Error> {
+        match DISTANZE_BUCHAREST
+            .iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(nome))
+        {
+            Some((c, _)) => Ok(c),
+            None => Err(Error::InvalidProblem(format!(
+                "Città non presente sulla mappa: {}",
+                nome
+            ))),
+        }
+    }
+    pub fn arrivo(&self) -> City {
+        self.arrivo
+    }
+}
+
+impl SearchProblem for RomaniaMap {
+    type State = City;
+
+    fn stato_iniziale(&self) -> City {
+        self.partenza
+    }
+    fn goal_test(&self, stato: &City) -> bool {
+        *stato == self.arrivo
+    }
+    fn successori(&self, stato: &City) -> impl Iterator<Item = Action<City>> {
+        STRADE.iter().filter_map(move |&(a, b, costo)| {
+            if a == *stato {
+                Some(Action {
+                    risultato: b,
+                    costo,
+                })
+            } else if b == *stato {
+                Some(Action {
+                    risultato: a,
+                    costo,
+                })
+            } else {
+                None
+            }
+        })
+    }
+    /// The roads can be travelled both ways
+    fn predecessori(&self, stato: &City) -> Option<impl Iterator<Item = Action<City>>> {
+        Some(self.successori(stato))
+    }
+    fn stato_finale(&self) -> Option<City> {
+        Some(self.arrivo)
+    }
+}
+
+/// Straight-line distance to Bucharest: only defined when Bucharest is the destination
+pub struct StraightLine;
+
+impl Heuristic<City> for StraightLine {
+    fn h(&self, state: &City) -> i32 {
+        DISTANZE_BUCHAREST
+            .iter()
+            .find(|(c, _)| c == state)
+            .map_or(0, |&(_, d)| d)
+    }
+}
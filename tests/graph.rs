@@ -0,0 +1,831 @@
+extern crate crc32fast;
+extern crate eia;
+extern crate flate2;
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use eia::problem::binary;
+use eia::problem::compression::*;
+use eia::problem::export::{self, FormatoExport, OpzioniExport};
+use eia::problem::formats::*;
+use eia::problem::graph::*;
+use eia::problem::heuristic::*;
+use eia::problem::stats::GraphStats;
+use eia::problem::Modalita;
+use eia::{Error, Graph, Problem, Search, SearchProblem, SearchResult};
+
+/// Writes a gzipped dataset in the temporary directory and returns its path
+fn dataset(nome: &str, contenuto: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("eia-test-{}-{}.gz", std::process::id(), nome));
+    let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+    encoder.write_all(contenuto.as_bytes()).unwrap();
+    encoder.finish().unwrap();
+    path
+}
+
+const DIRETTO: &str =
+    "# Directed graph\n# FromNodeId\tToNodeId\n0\t1\n1\t2\n2\t3\n3\t0\n0\t4\n4\t5\n";
+const NON_DIRETTO: &str =
+    "# Undirected graph\n# FromNodeId\tToNodeId\n0\t1\n1\t2\n2\t3\n0\t4\n4\t3\n3\t5\n";
+const ETICHETTATO: &str = "0,1,5,1\n1,2,-1,1\n0,2,10,1\n2,3,2,1\n";
+
+#[test]
+fn load_directed_graph() {
+    let grafo = Graph::from_file(dataset("load-directed", DIRETTO).to_str().unwrap()).unwrap();
+    assert_eq!(grafo.num_nodi(), 6);
+    assert!(grafo.is_directed());
+    assert_eq!(grafo.vicini(0), &[1, 4]);
+    assert!(grafo.vicini(5).is_empty());
+    let inverse: Vec<u32> = grafo.azioni_inverse(0).map(|a| a.risultato).collect();
+    assert_eq!(inverse, vec![3]);
+}
+
+#[test]
+fn bi_directional_matches_breadth_first_on_directed_graph() {
+    let path = dataset("bidir-directed", DIRETTO);
+    let problema = Problem::new("1", "5", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    let bfs = ricerca.breadth_first_search().0;
+    let bidir = ricerca.bi_directional_search().0;
+    match (&bfs, &bidir) {
+        (SearchResult::Found(a), SearchResult::Found(b)) => {
+            assert_eq!(a.profondita(), 5);
+            assert_eq!(b.profondita(), a.profondita());
+            assert_eq!(b.stati, vec![1, 2, 3, 0, 4, 5]);
+        }
+        _ => panic!("cammino non trovato"),
+    }
+}
+
+#[test]
+fn bi_directional_joins_both_halves() {
+    // following the edges forwards from the goal would reach 2 from 4
+    let path = dataset(
+        "bidir-joined",
+        "# Directed weighted graph\n0\t1\t2\n1\t2\t3\n2\t3\t4\n3\t4\t5\n4\t2\t1\n",
+    );
+    let problema = Problem::new("0", "4", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    match ricerca.bi_directional_search().0 {
+        SearchResult::Found(soluzione) => {
+            assert_eq!(soluzione.stati, vec![0, 1, 2, 3, 4]);
+            assert_eq!(soluzione.costi, vec![2, 3, 4, 5]);
+            assert_eq!(soluzione.profondita(), 4);
+            assert_eq!(soluzione.costo(), 14);
+        }
+        _ => panic!("cammino non trovato"),
+    }
+    // the goal is tested on the initial state as well
+    let problema = Problem::new("2", "2", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    match ricerca.bi_directional_search().0 {
+        SearchResult::Found(soluzione) => assert_eq!(soluzione.stati, vec![2]),
+        _ => panic!("cammino non trovato"),
+    }
+}
+
+#[test]
+fn every_strategy_reaches_the_goal_on_undirected_graph() {
+    let path = dataset("undirected", NON_DIRETTO);
+    let problema = Problem::new("0", "5", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    let risultati = vec![
+        ricerca.breadth_first_search().0,
+        ricerca.uniform_cost_search().0,
+        ricerca.iterative_deepening_search().0,
+        ricerca.bi_directional_search().0,
+        ricerca.a_star_search().0,
+        ricerca.bellman_ford_search().0,
+    ];
+    for risultato in risultati {
+        match risultato {
+            SearchResult::Found(soluzione) => {
+                assert_eq!(soluzione.profondita(), 3);
+                assert_eq!(soluzione.stati.first(), Some(&0));
+                assert_eq!(soluzione.stati.last(), Some(&5));
+            }
+            _ => panic!("cammino non trovato"),
+        }
+    }
+}
+
+#[test]
+fn uniform_cost_tests_the_goal_on_pop() {
+    // the goal is generated first by the expensive edge, then reached again for less
+    let path = dataset(
+        "uniform-cost",
+        "# Undirected weighted graph\n0\t1\t10\n0\t2\t1\n2\t3\t1\n3\t1\t1\n",
+    );
+    let problema = Problem::new("0", "1", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    match ricerca.uniform_cost_search().0 {
+        SearchResult::Found(soluzione) => {
+            assert_eq!(soluzione.stati, vec![0, 2, 3, 1]);
+            assert_eq!(soluzione.costo(), 3);
+        }
+        _ => panic!("cammino non trovato"),
+    }
+    match ricerca.breadth_first_search().0 {
+        SearchResult::Found(soluzione) => assert_eq!(soluzione.costo(), 10),
+        _ => panic!("cammino non trovato"),
+    }
+}
+
+#[test]
+fn negative_costs_are_rejected_or_reported() {
+    let path = dataset("labeled", ETICHETTATO);
+    let problema = Problem::new("0", "3", path.to_str().unwrap()).unwrap();
+    assert!(problema.ha_costi_negativi());
+    let mut ricerca = Search::new(&problema);
+    assert!(ricerca.uniform_cost_search().0 == SearchResult::NegativeCost);
+    // the informed searches reject them too, without the statistics of the previous run
+    assert!(ricerca.breadth_first_search().0 != SearchResult::NegativeCost);
+    let (risultato, statistiche) = ricerca.a_star_search();
+    assert!(risultato == SearchResult::NegativeCost);
+    assert_eq!(statistiche.espansi, 0);
+    assert!(ricerca.breadth_first_search().0 != SearchResult::NegativeCost);
+    let (risultato, statistiche) = ricerca.greedy_best_first_search();
+    assert!(risultato == SearchResult::NegativeCost);
+    assert_eq!(statistiche.espansi, 0);
+    // the labeled graph is undirected: the negative edge is a negative cycle
+    match ricerca.bellman_ford_search().0 {
+        SearchResult::NegativeCycle(ciclo) => {
+            assert_eq!(ciclo.first(), ciclo.last());
+            assert!(ciclo.contains(&1) && ciclo.contains(&2));
+        }
+        _ => panic!("ciclo negativo non trovato"),
+    }
+}
+
+#[test]
+fn path_costs_saturate() {
+    let path = dataset(
+        "huge-costs",
+        "# Directed weighted graph\n0\t1\t2000000000\n1\t2\t2000000000\n",
+    );
+    let problema = Problem::new("0", "2", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    for risultato in [
+        ricerca.uniform_cost_search().0,
+        ricerca.a_star_search().0,
+        ricerca.bi_directional_search().0,
+    ] {
+        match risultato {
+            SearchResult::Found(soluzione) => assert_eq!(soluzione.costo(), i32::MAX),
+            _ => panic!("cammino non trovato"),
+        }
+    }
+    // the distances from the landmarks saturate as well, and are then not used
+    let landmark = LandmarkHeuristic::new(problema.grafo(), &[0, 1], 2).unwrap();
+    assert_eq!(landmark.h(&0), 0);
+    assert_eq!(landmark.h(&1), 2_000_000_000);
+}
+
+#[test]
+fn bellman_ford_handles_negative_costs() {
+    // 4 and 5 form a negative cycle that 0 cannot reach
+    let path = dataset(
+        "bellman-ford",
+        "# Directed weighted graph\n0\t1\t4\n0\t2\t5\n2\t1\t-3\n1\t3\t1\n4\t5\t-1\n5\t4\t-1\n",
+    );
+    let problema = Problem::new("0", "3", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    assert!(ricerca.uniform_cost_search().0 == SearchResult::NegativeCost);
+    match ricerca.bellman_ford_search().0 {
+        SearchResult::Found(soluzione) => {
+            assert_eq!(soluzione.stati, vec![0, 2, 1, 3]);
+            assert_eq!(soluzione.costo(), 3);
+        }
+        _ => panic!("cammino non trovato"),
+    }
+    // from 4 the cycle is reachable
+    let problema = Problem::new("4", "3", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    match ricerca.bellman_ford_search().0 {
+        SearchResult::NegativeCycle(ciclo) => {
+            assert_eq!(ciclo.first(), ciclo.last());
+            assert!(ciclo.contains(&4) && ciclo.contains(&5));
+        }
+        _ => panic!("ciclo negativo non trovato"),
+    }
+}
+
+#[test]
+fn loading_errors_are_reported() {
+    match Graph::from_file("/file/inesistente.txt.gz") {
+        Err(Error::File { .. }) => {}
+        _ => panic!("file inesistente caricato"),
+    }
+    let malformato = dataset("malformed", "# Directed graph\n0\t1\n1\n");
+    match Graph::from_file(malformato.to_str().unwrap()) {
+        Err(Error::MalformedLine { riga, .. }) => assert_eq!(riga, 3),
+        _ => panic!("riga non valida accettata"),
+    }
+    let path = dataset("states", "# Directed graph\n0\t1\n3\t1\n");
+    let path = path.to_str().unwrap();
+    match Problem::new("0", "2", path) {
+        Err(e @ Error::UnknownLabel(_)) => assert_eq!(e.codice_uscita(), 6),
+        _ => panic!("nodo inesistente accettato"),
+    }
+    let grafo = Graph::from_file(path).unwrap();
+    match Problem::from_graph(0, 7, grafo) {
+        Err(Error::StateOutOfRange { num_stati, .. }) => assert_eq!(num_stati, 3),
+        _ => panic!("stato inesistente accettato"),
+    }
+}
+
+#[test]
+fn plain_text_datasets_are_loaded() {
+    let path = env::temp_dir().join(format!("eia-test-{}-plain.txt", std::process::id()));
+    std::fs::write(&path, DIRETTO.replace('\n', "\r\n")).unwrap();
+    let grafo = Graph::from_file(path.to_str().unwrap()).unwrap();
+    assert!(grafo.is_directed());
+    assert_eq!(grafo.num_nodi(), 6);
+}
+
+#[test]
+fn compression_is_detected() {
+    let gzip = std::fs::read(dataset("magic", DIRETTO)).unwrap();
+    assert_eq!(Compressione::da_magic(&gzip), Some(Compressione::Gzip));
+    assert_eq!(
+        Compressione::da_magic(b"BZh91AY"),
+        Some(Compressione::Bzip2)
+    );
+    assert_eq!(
+        Compressione::da_magic(&[0x28, 0xb5, 0x2f, 0xfd, 0]),
+        Some(Compressione::Zstd)
+    );
+    assert_eq!(Compressione::da_magic(b"# Directed"), None);
+    assert_eq!(
+        Compressione::da_estensione("data/roadNet-CA.txt.bz2"),
+        Some(Compressione::Bzip2)
+    );
+    assert_eq!(Compressione::da_estensione("data/roadNet-CA.txt"), None);
+}
+
+#[test]
+fn load_options_override_the_header() {
+    let path = dataset("options", "# Undirected graph\nx;0;1;4\nx;1;2;-3\n");
+    let opzioni = LoadOptions {
+        tipo: Some(GraphType::Directed),
+        pesato: Some(true),
+        colonna_da: 1,
+        colonna_a: 2,
+        colonna_peso: 3,
+        separatore: Some(';'),
+        formato: None,
+        duplicati: Duplicati::KeepFirst,
+        thread: 1,
+        riepilogo: false,
+    };
+    let grafo = Graph::from_file_with_options(path.to_str().unwrap(), &opzioni).unwrap();
+    assert!(grafo.is_directed() && grafo.is_weighted());
+    assert_eq!(grafo.vicini(1), &[2]);
+    assert_eq!(grafo.costi(1), &[-3]);
+    assert!(grafo.vicini(2).is_empty());
+
+    // a required weight can't be missing
+    let path = dataset("missing-weight", DIRETTO);
+    let opzioni = LoadOptions {
+        pesato: Some(true),
+        ..LoadOptions::default()
+    };
+    match Graph::from_file_with_options(path.to_str().unwrap(), &opzioni) {
+        Err(Error::MalformedLine { riga, .. }) => assert_eq!(riga, 3),
+        _ => panic!("peso mancante accettato"),
+    }
+
+    // the options win over the header
+    let path = dataset("header-override", "# Directed weighted graph\n0 1\n1 2\n");
+    let opzioni = LoadOptions {
+        tipo: Some(GraphType::Undirected),
+        pesato: Some(false),
+        ..LoadOptions::default()
+    };
+    let grafo = Graph::from_file_with_options(path.to_str().unwrap(), &opzioni).unwrap();
+    assert!(!grafo.is_directed() && !grafo.is_weighted());
+    assert_eq!(grafo.vicini(1), &[0, 2]);
+}
+
+#[test]
+fn duplicate_edges_follow_the_policy() {
+    let path = dataset("duplicates", "0 1 5\n1 0 2\n0 1 3\n1 2 4\n1 1 7\n1 1 1\n");
+    let path = path.to_str().unwrap();
+    let attesi = vec![
+        (Duplicati::KeepFirst, [5, 7, 4]),
+        (Duplicati::KeepMin, [2, 1, 4]),
+        (Duplicati::Sum, [10, 8, 4]),
+    ];
+    for (duplicati, costi) in attesi {
+        let opzioni = LoadOptions {
+            duplicati,
+            ..LoadOptions::default()
+        };
+        let grafo = Graph::from_file_with_options(path, &opzioni).unwrap();
+        assert_eq!(grafo.num_archi(), 3);
+        assert_eq!(grafo.vicini(1), &[0, 1, 2]);
+        assert_eq!(grafo.costi(1), &costi);
+        assert_eq!(grafo.costi(0), &costi[..1]);
+    }
+    let opzioni = LoadOptions {
+        duplicati: Duplicati::Error,
+        ..LoadOptions::default()
+    };
+    match Graph::from_file_with_options(path, &opzioni) {
+        Err(e @ Error::DuplicateEdge { .. }) => assert_eq!(e.codice_uscita(), 5),
+        _ => panic!("arco duplicato accettato"),
+    }
+
+    // unweighted duplicates are dropped, every directed edge is counted
+    let grafo = carica(
+        "duplicates-directed.txt",
+        "# Directed graph\n0\t1\n0\t1\n1\t0\n",
+    );
+    assert_eq!(grafo.vicini(0), &[1]);
+    assert_eq!(grafo.num_archi(), 2);
+}
+
+#[test]
+fn sparse_and_textual_labels_are_remapped() {
+    let path = dataset("sparse", "# Directed graph\n4000000000\t7\n7\t12\n");
+    let grafo = Graph::from_file(path.to_str().unwrap()).unwrap();
+    assert_eq!(grafo.num_nodi(), 3);
+    assert_eq!(grafo.stato("4000000000").unwrap(), 0);
+    assert_eq!(grafo.etichetta(2), "12");
+    assert!(grafo.stato("007").is_err());
+
+    let path = dataset("textual", "# Undirected graph\n1 alice\nalice bob\nbob 1\n");
+    let problema = Problem::new("1", "bob", path.to_str().unwrap()).unwrap();
+    assert_eq!(problema.grafo().etichetta(1), "alice");
+    let mut ricerca = Search::new(&problema);
+    match ricerca.breadth_first_search().0 {
+        SearchResult::Found(soluzione) => {
+            let etichette = soluzione.map(|s| problema.etichetta(s));
+            assert_eq!(etichette.stati, vec!["1", "bob"]);
+        }
+        _ => panic!("cammino non trovato"),
+    }
+}
+
+#[test]
+fn binary_format_round_trip() {
+    let path = dataset("binary-source", "a,b,3\nb,c,-2\nc,a,4\n");
+    let grafo = Graph::from_file(path.to_str().unwrap()).unwrap();
+    let binario = env::temp_dir().join(format!("eia-test-{}-graph.eia", std::process::id()));
+    let binario = binario.to_str().unwrap();
+    binary::scrivi(&grafo, binario).unwrap();
+    assert!(binary::is_binary(binario));
+
+    let caricato = Graph::from_file(binario).unwrap();
+    assert_eq!(caricato.num_nodi(), grafo.num_nodi());
+    assert_eq!(caricato.num_archi(), grafo.num_archi());
+    assert_eq!(caricato.is_weighted(), grafo.is_weighted());
+    for s in 0..grafo.num_nodi() as u32 {
+        assert_eq!(caricato.vicini(s), grafo.vicini(s));
+        assert_eq!(caricato.costi(s), grafo.costi(s));
+        assert_eq!(caricato.etichetta(s), grafo.etichetta(s));
+    }
+    assert_eq!(caricato.stato("c").unwrap(), 2);
+
+    // any change is caught by the checksum
+    let mut dati = std::fs::read(binario).unwrap();
+    dati[20] ^= 1;
+    std::fs::write(binario, dati).unwrap();
+    match Graph::from_file(binario) {
+        Err(e @ Error::BinaryFormat { .. }) => assert_eq!(e.codice_uscita(), 10),
+        _ => panic!("file binario corrotto caricato"),
+    }
+}
+
+#[test]
+fn binary_format_rejects_huge_sizes() {
+    let binario = env::temp_dir().join(format!("eia-test-{}-huge.eia", std::process::id()));
+    let binario = binario.to_str().unwrap();
+    for &(n, azioni) in &[(u64::MAX, 0), (u64::MAX / 8, 0), (0, u64::MAX / 2)] {
+        let mut dati = binary::MAGIC.to_vec();
+        dati.extend_from_slice(&binary::VERSIONE.to_le_bytes());
+        dati.extend_from_slice(&0u32.to_le_bytes());
+        dati.extend_from_slice(&n.to_le_bytes());
+        dati.extend_from_slice(&azioni.to_le_bytes());
+        dati.extend_from_slice(&0u64.to_le_bytes());
+        dati.extend_from_slice(&0u64.to_le_bytes());
+        let crc = crc32fast::hash(&dati);
+        dati.extend_from_slice(&crc.to_le_bytes());
+        std::fs::write(binario, dati).unwrap();
+        match Graph::from_file(binario) {
+            Err(Error::BinaryFormat { .. }) => {}
+            _ => panic!("intestazione con {} stati e {} azioni caricata", n, azioni),
+        }
+    }
+}
+
+/// Loads `contenuto` as a dataset with the given extension
+fn carica(nome: &str, contenuto: &str) -> Graph {
+    let path = env::temp_dir().join(format!("eia-test-{}-{}", std::process::id(), nome));
+    std::fs::write(&path, contenuto).unwrap();
+    Graph::from_file(path.to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn other_formats_are_read() {
+    let mtx = carica(
+        "matrix.mtx",
+        "%%MatrixMarket matrix coordinate real general\n% commento\n3 3 2\n1 2 1.6\n2 3 4\n",
+    );
+    assert!(mtx.is_directed() && mtx.is_weighted());
+    assert_eq!(mtx.costi(mtx.stato("1").unwrap()), &[2]);
+    let antisimmetrica = carica(
+        "skew.mtx",
+        "%%MatrixMarket matrix coordinate real skew-symmetric\n3 3 1\n2 1 3\n",
+    );
+    assert!(antisimmetrica.is_directed());
+    assert_eq!(
+        antisimmetrica.costi(antisimmetrica.stato("2").unwrap()),
+        &[3]
+    );
+    assert_eq!(
+        antisimmetrica.costi(antisimmetrica.stato("1").unwrap()),
+        &[-3]
+    );
+    for simmetria in &["complex general", "real hermitian"] {
+        let path = dataset(
+            "refused.mtx",
+            &format!(
+                "%%MatrixMarket matrix coordinate {}\n3 3 1\n2 1 3\n",
+                simmetria
+            ),
+        );
+        match Graph::from_file(path.to_str().unwrap()) {
+            Err(Error::MalformedLine { riga, .. }) => assert_eq!(riga, 1),
+            _ => panic!("matrice {} accettata", simmetria),
+        }
+    }
+
+    let gr = carica("road.gr", "c strade\np sp 3 3\na 1 2 7\na 2 3 5\na 3 1 2\n");
+    assert!(gr.is_directed());
+    assert_eq!(gr.vicini(gr.stato("3").unwrap()), &[gr.stato("1").unwrap()]);
+
+    let graphml = carica(
+        "graph.graphml",
+        r#"<?xml version="1.0"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="w" for="edge" attr.name="weight" attr.type="double"/>
+  <graph edgedefault="undirected">
+    <node id="a"/><node id="b"/><node id="c"/><node id="isolato"/>
+    <edge source="a" target="b"><data key="w">3</data></edge>
+    <edge source="b" target="c"><data key="w">1</data></edge>
+  </graph>
+</graphml>"#,
+    );
+    assert!(!graphml.is_directed() && graphml.is_weighted());
+    assert_eq!(graphml.num_nodi(), 4);
+    assert!(graphml.is_isolated(graphml.stato("isolato").unwrap()));
+    assert_eq!(graphml.costi(graphml.stato("c").unwrap()), &[1]);
+
+    let json = carica(
+        "edges.json",
+        r#"{"directed": false, "nodes": [{"id": 1}, {"id": "x"}],
+            "edges": [{"source": 1, "target": "x", "weight": 2}, [1, 5, 4]]}"#,
+    );
+    assert!(!json.is_directed() && json.is_weighted());
+    assert_eq!(json.num_nodi(), 3);
+    assert_eq!(json.vicini(json.stato("1").unwrap()).len(), 2);
+
+    let array = carica("array.json", r#"[["a", "b"], ["b", "c"]]"#);
+    assert!(array.is_directed() && !array.is_weighted());
+    assert_eq!(array.num_nodi(), 3);
+}
+
+#[test]
+fn format_is_chosen_by_extension() {
+    assert_eq!(Formato::da_estensione("usa.gr.gz"), Formato::Dimacs);
+    assert_eq!(Formato::da_estensione("m.mtx"), Formato::Mtx);
+    assert_eq!(Formato::da_estensione("g.graphml.bz2"), Formato::Graphml);
+    assert_eq!(Formato::da_estensione("g.json"), Formato::Json);
+    assert_eq!(
+        Formato::da_estensione("data/email-Enron.txt.gz"),
+        Formato::Snap
+    );
+    let malformato = dataset("broken.json", "{\"edges\": [");
+    match Graph::from_file(malformato.to_str().unwrap()) {
+        Err(Error::InvalidDocument { .. }) => {}
+        _ => panic!("documento non valido accettato"),
+    }
+}
+
+#[test]
+fn export_colors_the_search() {
+    let path = dataset("export", NON_DIRETTO);
+    let problema = Problem::new("0", "5", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    let cammino = match ricerca.breadth_first_search().0 {
+        SearchResult::Found(soluzione) => soluzione.stati,
+        _ => panic!("cammino non trovato"),
+    };
+    assert_eq!(cammino, vec![0, 4, 3, 5]);
+    let esplorazione = ricerca.esplorazione();
+    assert!(esplorazione.espansi.contains(&0));
+    assert!(esplorazione.frontiera.contains(&5));
+    assert!(esplorazione.espansi.is_disjoint(&esplorazione.frontiera));
+    let grafo = problema.grafo();
+    let opzioni = OpzioniExport {
+        cammino,
+        esplorazione,
+        ..OpzioniExport::default()
+    };
+
+    let mut dot = Vec::new();
+    export::esporta(grafo, FormatoExport::Dot, &opzioni, &mut dot).unwrap();
+    let dot = String::from_utf8(dot).unwrap();
+    assert!(dot.starts_with("graph eia {"));
+    assert!(dot.contains("\"0\" [style=filled, fillcolor=red];"));
+    assert!(dot.contains("\"3\" -- \"5\" [color=red, penwidth=2];"));
+    assert!(dot.contains("\"1\" -- \"2\";"));
+
+    // GraphML and edge lists are read back
+    let graphml = env::temp_dir().join(format!("eia-test-{}-export.graphml", std::process::id()));
+    let graphml = graphml.to_str().unwrap();
+    export::scrivi(
+        grafo,
+        graphml,
+        FormatoExport::da_estensione(graphml),
+        &opzioni,
+    )
+    .unwrap();
+    let riletto = Graph::from_file(graphml).unwrap();
+    assert_eq!(riletto.num_nodi(), grafo.num_nodi());
+    assert_eq!(riletto.num_archi(), grafo.num_archi());
+    assert!(!riletto.is_directed());
+
+    let sottografo = OpzioniExport {
+        stati: Some(export::intorno(grafo, &opzioni.cammino, 0)),
+        ..opzioni
+    };
+    let lista = env::temp_dir().join(format!("eia-test-{}-export.txt", std::process::id()));
+    let lista = lista.to_str().unwrap();
+    export::scrivi(grafo, lista, FormatoExport::Edgelist, &sottografo).unwrap();
+    let riletto = Graph::from_file(lista).unwrap();
+    assert_eq!(riletto.num_nodi(), 4);
+    assert_eq!(riletto.num_archi(), 3);
+    assert!(riletto.stato("1").is_err());
+}
+
+#[test]
+fn weighted_edge_list_round_trip() {
+    let grafo = carica("weighted.csv", "a,b,5\nb,c,-2\nc,a,7\na,a,1\nd,e,3\n");
+    assert!(!grafo.is_directed() && grafo.is_weighted());
+    let lista = env::temp_dir().join(format!("eia-test-{}-weighted.txt", std::process::id()));
+    let lista = lista.to_str().unwrap();
+    export::scrivi(
+        &grafo,
+        lista,
+        FormatoExport::Edgelist,
+        &OpzioniExport::default(),
+    )
+    .unwrap();
+    let riletto = Graph::from_file(lista).unwrap();
+    assert!(!riletto.is_directed() && riletto.is_weighted());
+    assert_eq!(riletto.num_nodi(), grafo.num_nodi());
+    assert_eq!(riletto.num_archi(), grafo.num_archi());
+    for s in 0..grafo.num_nodi() as u32 {
+        let t = riletto.stato(&grafo.etichetta(s)).unwrap();
+        let archi = |g: &Graph, s| {
+            let mut archi: Vec<(String, i32)> = g
+                .azioni(s)
+                .map(|a| (g.etichetta(a.risultato), a.costo))
+                .collect();
+            archi.sort();
+            archi
+        };
+        assert_eq!(archi(&riletto, t), archi(&grafo, s));
+    }
+
+    // a directed unweighted graph keeps its type
+    let diretto = carica("unweighted.txt", DIRETTO);
+    export::scrivi(
+        &diretto,
+        lista,
+        FormatoExport::Edgelist,
+        &OpzioniExport::default(),
+    )
+    .unwrap();
+    let riletto = Graph::from_file(lista).unwrap();
+    assert!(riletto.is_directed() && !riletto.is_weighted());
+    assert_eq!(riletto.num_archi(), diretto.num_archi());
+}
+
+#[test]
+fn parallel_loading_matches_sequential() {
+    // enough lines for several blocks, with textual labels after the first ones
+    let mut contenuto = String::new();
+    let mut x: u64 = 7;
+    for i in 0..40_000u64 {
+        x = x
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let (da, a, costo) = ((x >> 33) % 5000, (x >> 13) % 5000, (x >> 50) % 100);
+        if i > 20_000 && i % 7 == 0 {
+            contenuto.push_str(&format!("n{}\t{}\t{}\n", da, a, costo));
+        } else {
+            contenuto.push_str(&format!("{}\t{}\t{}\n", da, a, costo));
+        }
+        if i % 1000 == 0 {
+            contenuto.push_str("# commento\n\n");
+        }
+    }
+    let path = dataset("parallel", &contenuto);
+    let path = path.to_str().unwrap();
+    let sequenziale = Graph::from_file(path).unwrap();
+    for &thread in &[2, 3, 8] {
+        let opzioni = LoadOptions {
+            thread,
+            ..LoadOptions::default()
+        };
+        let parallelo = Graph::from_file_with_options(path, &opzioni).unwrap();
+        assert_eq!(parallelo.num_nodi(), sequenziale.num_nodi());
+        assert_eq!(parallelo.num_archi(), sequenziale.num_archi());
+        assert_eq!(parallelo.is_weighted(), sequenziale.is_weighted());
+        for s in 0..sequenziale.num_nodi() as u32 {
+            assert_eq!(parallelo.etichetta(s), sequenziale.etichetta(s));
+            assert_eq!(parallelo.vicini(s), sequenziale.vicini(s));
+            assert_eq!(parallelo.costi(s), sequenziale.costi(s));
+        }
+    }
+
+    // the first malformed line is reported, as by the sequential loader
+    contenuto.push_str("1\n2\t3\tx\n");
+    let path = dataset("parallel-malformed", &contenuto);
+    let opzioni = LoadOptions {
+        thread: 4,
+        ..LoadOptions::default()
+    };
+    let righe = contenuto.lines().count();
+    match Graph::from_file_with_options(path.to_str().unwrap(), &opzioni) {
+        Err(Error::MalformedLine { riga, .. }) => assert_eq!(riga, righe - 1),
+        _ => panic!("riga non valida accettata"),
+    }
+}
+
+#[test]
+fn stats_describe_the_graph() {
+    let grafo = carica("stats.txt", &format!("{}6\t6\n", DIRETTO));
+    let statistiche = GraphStats::new(&grafo);
+    assert_eq!(statistiche.nodi, 7);
+    assert_eq!(statistiche.archi, 7);
+    assert_eq!(statistiche.cappi, 1);
+    assert_eq!(statistiche.isolati, 0);
+    assert_eq!(statistiche.grado_uscente.max(), 2);
+    assert_eq!(statistiche.grado_uscente.conteggi[&0], 1);
+    assert_eq!(statistiche.grado_entrante.min(), 1);
+    assert_eq!(statistiche.componenti_deboli.numero, 2);
+    assert_eq!(statistiche.componenti_deboli.piu_grande, 6);
+    assert_eq!(statistiche.componenti_forti.numero, 4);
+    assert_eq!(statistiche.componenti_forti.piu_grande, 4);
+    assert_eq!(statistiche.diametro, 4);
+    let json = statistiche.to_json();
+    assert_eq!(json["edges"], 7);
+    assert_eq!(json["strongly_connected_components"]["count"], 4);
+
+    // every undirected edge is counted once
+    let grafo = carica("stats-undirected.txt", NON_DIRETTO);
+    let statistiche = GraphStats::new(&grafo);
+    assert_eq!(statistiche.archi, 6);
+    assert_eq!(statistiche.componenti_forti, statistiche.componenti_deboli);
+    assert_eq!(statistiche.diametro, 3);
+}
+
+#[test]
+fn depth_limited_search_is_iterative() {
+    // a path deeper than the stack of a recursive search
+    let catena: String = (0..100_000)
+        .map(|i| format!("{}\t{}\n", i, i + 1))
+        .collect();
+    let path = dataset("chain", &format!("# Directed graph\n{}", catena));
+    let problema = Problem::new("0", "100000", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    ricerca.set_limite(100_000);
+    match ricerca.depth_limited_search().0 {
+        SearchResult::Found(soluzione) => assert_eq!(soluzione.profondita(), 100_000),
+        _ => panic!("cammino non trovato"),
+    }
+    ricerca.set_limite(99_999);
+    assert!(ricerca.depth_limited_search().0 == SearchResult::CutOff);
+
+    // with the cycle check the states on the path are never repeated
+    let path = dataset("cycles", "# Undirected graph\n0\t1\n1\t2\n2\t0\n3\t4\n");
+    let problema = Problem::new("0", "2", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    ricerca.set_controllo_cicli(true);
+    match ricerca.depth_limited_search().0 {
+        SearchResult::Found(soluzione) => assert_eq!(soluzione.stati, vec![0, 1, 2]),
+        _ => panic!("cammino non trovato"),
+    }
+    // and an unreachable goal is a failure, not a cutoff
+    let problema = Problem::new("0", "4", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    ricerca.set_controllo_cicli(true);
+    ricerca.set_limite(50);
+    assert!(ricerca.depth_limited_search().0 == SearchResult::Failure);
+}
+
+#[test]
+fn iterative_deepening_terminates() {
+    let path = dataset("ids", "# Undirected graph\n0\t1\n1\t2\n2\t0\n3\t4\n");
+    let problema = Problem::new("0", "4", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    // on a cyclic graph the unreachable goal is cut off up to the maximum depth
+    ricerca.set_profondita_massima(12);
+    assert!(ricerca.iterative_deepening_search().0 == SearchResult::CutOff);
+    // with the cycle check the paths end, so the search fails
+    ricerca.set_profondita_massima(usize::MAX);
+    ricerca.set_controllo_cicli(true);
+    assert!(ricerca.iterative_deepening_search().0 == SearchResult::Failure);
+
+    // the limit of the depth-limited search is kept
+    let problema = Problem::new("0", "2", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    ricerca.set_limite(1);
+    match ricerca.iterative_deepening_search().0 {
+        SearchResult::Found(soluzione) => assert_eq!(soluzione.stati, vec![0, 2]),
+        _ => panic!("cammino non trovato"),
+    }
+    assert!(ricerca.depth_limited_search().0 != SearchResult::CutOff);
+    ricerca.set_limite(0);
+    assert!(ricerca.depth_limited_search().0 == SearchResult::CutOff);
+}
+
+#[test]
+fn tree_and_graph_modes() {
+    let path = dataset(
+        "modes",
+        "# Undirected graph\n0\t1\n1\t2\n2\t0\n2\t3\n3\t4\n5\t6\n",
+    );
+    let problema = Problem::new("0", "4", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    let graph = ricerca.breadth_first_search().0;
+    assert!(graph != SearchResult::Failure);
+    ricerca.set_modalita(Modalita::Tree);
+    // the tree search finds the same shallowest path
+    assert!(ricerca.breadth_first_search().0 == graph);
+    assert!(ricerca.bellman_ford_search().0 == SearchResult::Unsupported);
+
+    // on a cyclic graph the tree search of an unreachable goal is stopped by the budget
+    let problema = Problem::new("0", "6", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    let (risultato, statistiche) = ricerca.tree_search();
+    assert!(risultato == SearchResult::BudgetExceeded);
+    assert_eq!(statistiche.espansi, 1_000_000);
+    ricerca.set_espansioni_massime(1000);
+    assert!(ricerca.tree_search().0 == SearchResult::BudgetExceeded);
+    assert!(ricerca.ida_star_search().0 == SearchResult::Failure);
+    assert!(ricerca.iterative_deepening_search().0 == SearchResult::BudgetExceeded);
+    // as graph searches they end
+    ricerca.set_modalita(Modalita::Graph);
+    for risultato in [
+        ricerca.tree_search().0,
+        ricerca.breadth_first_search().0,
+        ricerca.uniform_cost_search().0,
+        ricerca.bi_directional_search().0,
+        ricerca.iterative_deepening_search().0,
+        ricerca.ida_star_search().0,
+        ricerca.recursive_best_first_search().0,
+    ] {
+        assert!(risultato == SearchResult::Failure);
+    }
+}
+
+#[test]
+fn search_statistics() {
+    let path = dataset(
+        "statistics",
+        "# Undirected graph\n0\t1\n1\t2\n2\t0\n2\t3\n3\t4\n",
+    );
+    let problema = Problem::new("0", "4", path.to_str().unwrap()).unwrap();
+    let mut ricerca = Search::new(&problema);
+    let (risultato, statistiche) = ricerca.breadth_first_search();
+    assert!(risultato != SearchResult::Failure);
+    assert_eq!(statistiche.espansi, 4);
+    assert_eq!(statistiche.generati, 9);
+    assert_eq!(statistiche.duplicati, 5);
+    assert_eq!(statistiche.frontiera_massima, 2);
+    assert_eq!(statistiche.raggiunti_massimi, 4);
+    assert_eq!(statistiche.iterazioni, 0);
+
+    // the tree search discards nothing and expands the states reached again
+    ricerca.set_modalita(Modalita::Tree);
+    let (risultato, albero) = ricerca.breadth_first_search();
+    assert!(risultato != SearchResult::Failure);
+    assert_eq!(albero.duplicati, 0);
+    assert!(albero.espansi > statistiche.espansi);
+
+    // a depth limit for every depth up to the goal
+    let (risultato, statistiche) = ricerca.iterative_deepening_search();
+    assert!(risultato != SearchResult::Failure);
+    assert_eq!(statistiche.iterazioni, 4);
+}
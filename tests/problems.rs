@@ -0,0 +1,254 @@
+extern crate eia;
+
+use std::cell::RefCell;
+
+use eia::problems::maze::*;
+use eia::problems::puzzle::*;
+use eia::problems::queens::*;
+use eia::problems::romania::*;
+use eia::{Action, Search, SearchProblem, SearchResult, Solution};
+
+fn soluzione<S>(risultato: SearchResult<S>) -> Solution<S> {
+    match risultato {
+        SearchResult::Found(soluzione) => soluzione,
+        _ => panic!("soluzione non trovata"),
+    }
+}
+
+#[test]
+fn romania_uniform_cost_is_optimal() {
+    let problema = RomaniaMap::new("Arad", "Bucharest").unwrap();
+    let mut ricerca = Search::new(&problema);
+    let ucs = soluzione(ricerca.uniform_cost_search().0);
+    assert_eq!(ucs.costo(), 418);
+    assert_eq!(
+        ucs.stati,
+        vec!["Arad", "Sibiu", "RimnicuVilcea", "Pitesti", "Bucharest"]
+    );
+    assert_eq!(soluzione(ricerca.breadth_first_search().0).profondita(), 3);
+
+    ricerca.set_euristica(Box::new(StraightLine));
+    assert_eq!(soluzione(ricerca.a_star_search().0).costo(), 418);
+    assert_eq!(soluzione(ricerca.greedy_best_first_search().0).costo(), 450);
+}
+
+#[test]
+fn puzzle_a_star_is_as_short_as_breadth_first() {
+    let problema = SlidingPuzzle::new(vec![3, 2, 5, 6, 0, 8, 7, 1, 4]).unwrap();
+    let mut ricerca = Search::new(&problema);
+    let bfs = soluzione(ricerca.breadth_first_search().0);
+    assert_eq!(bfs.profondita(), 10);
+    ricerca.set_euristica(Box::new(ManhattanTiles));
+    assert_eq!(soluzione(ricerca.a_star_search().0).profondita(), 10);
+    ricerca.set_euristica(Box::new(MisplacedTiles));
+    assert_eq!(soluzione(ricerca.a_star_search().0).profondita(), 10);
+    assert_eq!(
+        soluzione(ricerca.bi_directional_search().0).profondita(),
+        10
+    );
+}
+
+#[test]
+fn linear_space_searches_are_optimal() {
+    let problema = RomaniaMap::new("Arad", "Bucharest").unwrap();
+    let mut ricerca = Search::new(&problema);
+    ricerca.set_euristica(Box::new(StraightLine));
+    let (risultato, statistiche) = ricerca.ida_star_search();
+    assert_eq!(soluzione(risultato).costo(), 418);
+    assert!(statistiche.iterazioni > 1);
+    assert!(statistiche.riespansioni > 0);
+    // RBFS goes back to Rimnicu Vilcea after exploring Fagaras
+    let (risultato, statistiche) = ricerca.recursive_best_first_search();
+    assert_eq!(soluzione(risultato).costo(), 418);
+    assert!(statistiche.iterazioni > 0);
+    assert!(statistiche.riespansioni > 0);
+
+    let problema = SlidingPuzzle::new(vec![3, 2, 5, 6, 0, 8, 7, 1, 4]).unwrap();
+    let mut ricerca = Search::new(&problema);
+    ricerca.set_euristica(Box::new(ManhattanTiles));
+    assert_eq!(soluzione(ricerca.ida_star_search().0).profondita(), 10);
+    assert_eq!(
+        soluzione(ricerca.recursive_best_first_search().0).profondita(),
+        10
+    );
+}
+
+#[test]
+fn invalid_instances_are_rejected() {
+    assert!(SlidingPuzzle::new(vec![0, 2, 1, 3, 4, 5, 6, 7, 8]).is_err());
+    assert!(SlidingPuzzle::new(vec![0, 1, 2]).is_err());
+    assert!(GridMaze::parse("#S.#\n").is_err());
+    assert!(RomaniaMap::new("Arad", "Roma").is_err());
+}
+
+#[test]
+fn queens_are_not_attacking() {
+    let problema = NQueens::new(6);
+    let mut ricerca = Search::new(&problema);
+    let regine = soluzione(ricerca.breadth_first_search().0);
+    let Queens(colonne) = regine.stati.last().unwrap().clone();
+    assert_eq!(colonne.len(), 6);
+    for i in 0..colonne.len() {
+        for j in i + 1..colonne.len() {
+            assert_ne!(colonne[i], colonne[j]);
+            assert_ne!(j - i, colonne[i].abs_diff(colonne[j]));
+        }
+    }
+    assert!(ricerca.bi_directional_search().0 == SearchResult::Unsupported);
+}
+
+#[test]
+fn maze_shortest_path() {
+    let problema = GridMaze::parse("#######\n#S..#G#\n#.#.#.#\n#.....#\n#######\n").unwrap();
+    assert_eq!(
+        problema.stato_iniziale(),
+        Cell {
+            riga: 1,
+            colonna: 1
+        }
+    );
+    let mut ricerca = Search::new(&problema);
+    assert_eq!(soluzione(ricerca.breadth_first_search().0).profondita(), 8);
+    ricerca.set_euristica(Box::new(ManhattanCells {
+        fine: Cell {
+            riga: 1,
+            colonna: 5,
+        },
+    }));
+    assert_eq!(soluzione(ricerca.a_star_search().0).profondita(), 8);
+}
+
+/// Counting up to `fine` by 1 or 2: the actions cannot be followed backwards
+struct Contatore {
+    fine: u32,
+}
+
+impl SearchProblem for Contatore {
+    type State = u32;
+
+    fn stato_iniziale(&self) -> u32 {
+        0
+    }
+    fn goal_test(&self, stato: &u32) -> bool {
+        *stato == self.fine
+    }
+    fn successori(&self, stato: &u32) -> impl Iterator<Item = Action<u32>> {
+        [1, 2]
+            .iter()
+            .filter(move |&&passo| stato + passo <= self.fine)
+            .map(move |&passo| Action {
+                risultato: stato + passo,
+                costo: 1,
+            })
+    }
+    fn stato_finale(&self) -> Option<u32> {
+        Some(self.fine)
+    }
+}
+
+#[test]
+fn bi_directional_needs_the_predecessors() {
+    let problema = Contatore { fine: 5 };
+    let mut ricerca = Search::new(&problema);
+    assert_eq!(soluzione(ricerca.breadth_first_search().0).profondita(), 3);
+    assert!(ricerca.bi_directional_search().0 == SearchResult::Unsupported);
+}
+
+/// Directed acyclic graph that records the states it expands
+struct Dag {
+    archi: Vec<Vec<u32>>,
+    fine: u32,
+    espansi: RefCell<Vec<u32>>,
+}
+
+impl SearchProblem for Dag {
+    type State = u32;
+
+    fn stato_iniziale(&self) -> u32 {
+        0
+    }
+    fn goal_test(&self, stato: &u32) -> bool {
+        *stato == self.fine
+    }
+    fn successori(&self, stato: &u32) -> impl Iterator<Item = Action<u32>> {
+        self.espansi.borrow_mut().push(*stato);
+        self.archi[*stato as usize].iter().map(|&risultato| Action {
+            risultato,
+            costo: 1,
+        })
+    }
+}
+
+/// The recursive depth-limited search that the iterative one replaced
+fn ricorsiva(
+    archi: &[Vec<u32>],
+    fine: u32,
+    limite: usize,
+    cammino: &mut Vec<u32>,
+    espansi: &mut Vec<u32>,
+) -> SearchResult<u32> {
+    let stato = *cammino.last().unwrap();
+    if stato == fine {
+        return SearchResult::Found(Solution {
+            stati: cammino.clone(),
+            costi: vec![1; cammino.len() - 1],
+        });
+    } else if cammino.len() - 1 == limite {
+        return SearchResult::CutOff;
+    }
+    espansi.push(stato);
+    let mut cutoff = false;
+    for &figlio in &archi[stato as usize] {
+        cammino.push(figlio);
+        let risultato = ricorsiva(archi, fine, limite, cammino, espansi);
+        cammino.pop();
+        match risultato {
+            SearchResult::CutOff => cutoff = true,
+            SearchResult::Failure => {}
+            risultato => return risultato,
+        }
+    }
+    if cutoff {
+        SearchResult::CutOff
+    } else {
+        SearchResult::Failure
+    }
+}
+
+#[test]
+fn depth_limited_matches_the_recursive_search() {
+    let archi: Vec<Vec<u32>> = vec![
+        vec![1, 2, 3],
+        vec![4, 5],
+        vec![4, 6],
+        vec![6, 7],
+        vec![8, 9],
+        vec![9],
+        vec![9, 10],
+        vec![10],
+        vec![11],
+        vec![11],
+        vec![11],
+        vec![],
+        // not reachable from 0
+        vec![11],
+    ];
+    for &fine in &[11, 9, 7, 0, 12] {
+        for limite in 0..=6 {
+            let mut atteso = Vec::new();
+            let risultato = ricorsiva(&archi, fine, limite, &mut vec![0], &mut atteso);
+            for &controllo_cicli in &[false, true] {
+                let problema = Dag {
+                    archi: archi.clone(),
+                    fine,
+                    espansi: RefCell::new(Vec::new()),
+                };
+                let mut ricerca = Search::new(&problema);
+                ricerca.set_limite(limite);
+                ricerca.set_controllo_cicli(controllo_cicli);
+                assert!(ricerca.depth_limited_search().0 == risultato);
+                assert_eq!(*problema.espansi.borrow(), atteso);
+            }
+        }
+    }
+}